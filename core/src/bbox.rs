@@ -4,7 +4,7 @@
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{cmp::Ordering, fmt};
 use tracing::trace;
 
 /// The result of an horizontal split.
@@ -106,6 +106,12 @@ impl BBox {
     /// Equally splits the surface area of a bounding box, returning the
     /// resulting bounding boxes.
     ///
+    /// The bounding boxes are placed next to each other, starting at the
+    /// root's origin. Pixels that are left over because the width or height
+    /// isn't divisible by `number_of_bboxes` are handed out one by one to the
+    /// first bounding boxes, so the parts always exactly cover the root
+    /// bounding box.
+    ///
     /// # Arguments
     ///
     /// - `root`: The bounding box that gets split up.
@@ -118,46 +124,109 @@ impl BBox {
     ///
     /// - If `number_of_bboxes` is 0, this function is a no-op and returns an
     ///   empty `Vec` with an initial capacity of zero.
+    /// - If `number_of_bboxes` is bigger than the width or height that gets
+    ///   split, the first bounding boxes are one pixel wide or high and the
+    ///   remaining ones are empty and placed at the far edge of the root.
     #[tracing::instrument]
     pub fn equal_split(
         root: BBox,
         number_of_bboxes: usize,
         split_direction: SplitDirection,
     ) -> Vec<BBox> {
-        // TODO: check if number_of_bboxes(x) > width/height, as we can't move x times
-        // if the with is smaller than x
         trace!("equal_split");
         if number_of_bboxes == 0 {
             return Vec::with_capacity(0);
         }
 
-        let mut bboxes = Vec::with_capacity(number_of_bboxes);
+        let length = match split_direction {
+            SplitDirection::Horizontal => root.height,
+            SplitDirection::Vertical => root.width,
+        };
+        let length = length.max(0) as usize;
+        let size_per_part = length / number_of_bboxes;
+        let remainder = length % number_of_bboxes;
+
+        let sizes = (0..number_of_bboxes).map(|i| {
+            if i < remainder {
+                size_per_part as i32 + 1
+            } else {
+                size_per_part as i32
+            }
+        });
+        split_along(root, sizes, split_direction)
+    }
 
-        for i in 0..number_of_bboxes {
-            let bbox = match split_direction {
-                SplitDirection::Horizontal => {
-                    let height_per_part = root.height / number_of_bboxes as i32;
-                    BBox::with(
-                        root.x,
-                        (i * height_per_part as usize) as i32,
-                        root.width,
-                        height_per_part,
-                    )
-                }
-                SplitDirection::Vertical => {
-                    let width_per_part = root.width / number_of_bboxes as i32;
-                    BBox::with(
-                        (i * width_per_part as usize) as i32,
-                        root.y,
-                        width_per_part,
-                        root.height,
-                    )
-                }
-            };
-            bboxes.push(bbox);
-        }
+    /// Splits the surface area of a bounding box according to the given
+    /// weights, returning the resulting bounding boxes.
+    ///
+    /// Each part receives a share of the root's width or height that is
+    /// proportional to its weight. Pixels that are left over due to integer
+    /// rounding are handed out one by one to the parts with the largest
+    /// fractional share, so the parts always exactly cover the root bounding
+    /// box.
+    ///
+    /// # Arguments
+    ///
+    /// - `root`: The bounding box that gets split up.
+    /// - `weights`: The weight of each part. Negative and non-finite weights
+    ///   are treated as zero.
+    /// - `split_direction`: Whether to split the bounding box horizontally or
+    ///   vertically.
+    ///
+    /// # Special cases
+    ///
+    /// - If `weights` is empty, this function returns an empty `Vec`.
+    /// - If no weight is positive, the root bounding box is split equally.
+    #[tracing::instrument]
+    pub fn weighted_split(
+        root: BBox,
+        weights: &[f64],
+        split_direction: SplitDirection,
+    ) -> Vec<BBox> {
+        let length = match split_direction {
+            SplitDirection::Horizontal => root.height,
+            SplitDirection::Vertical => root.width,
+        };
 
-        bboxes
+        split_along(root, distribute(length, weights), split_direction)
+    }
+
+    /// Splits the current bounding box horizontally by a ratio and returns the
+    /// result.
+    ///
+    /// # Arguments
+    ///
+    /// - `ratio`: The share of the height that the upper bounding box receives.
+    ///   The value is clamped to `0.0..=1.0`. `NaN` splits the bounding box in
+    ///   half.
+    #[tracing::instrument]
+    pub fn horizontal_split_by(&self, ratio: f64) -> HorizontalSplit {
+        let upper_height = scale(self.height, ratio);
+        let upper = BBoxBuilder::from(*self).height(upper_height).build();
+        let lower = BBoxBuilder::from(*self)
+            .y(self.y + upper_height)
+            .height(self.height - upper_height)
+            .build();
+        HorizontalSplit::with(upper, lower)
+    }
+
+    /// Splits the current bounding box vertically by a ratio and returns the
+    /// result.
+    ///
+    /// # Arguments
+    ///
+    /// - `ratio`: The share of the width that the left bounding box receives.
+    ///   The value is clamped to `0.0..=1.0`. `NaN` splits the bounding box in
+    ///   half.
+    #[tracing::instrument]
+    pub fn vertical_split_by(&self, ratio: f64) -> VerticalSplit {
+        let left_width = scale(self.width, ratio);
+        let left = BBoxBuilder::from(*self).width(left_width).build();
+        let right = BBoxBuilder::from(*self)
+            .x(self.x + left_width)
+            .width(self.width - left_width)
+            .build();
+        VerticalSplit::with(left, right)
     }
 
     /// Splits the current bounding box horizontally and returns the result.
@@ -191,6 +260,327 @@ impl BBox {
             .build();
         VerticalSplit::with(left, right)
     }
+
+    /// Creates a bounding box from the positions of its edges.
+    ///
+    /// # Arguments
+    ///
+    /// - `left`: The x-position of the left edge.
+    /// - `top`: The y-position of the top edge.
+    /// - `right`: The exclusive x-position of the right edge.
+    /// - `bottom`: The exclusive y-position of the bottom edge.
+    ///
+    /// # Note
+    ///
+    /// All values saturate at the bounds of `i32`.
+    pub fn from_edges(left: i64, top: i64, right: i64, bottom: i64) -> Self {
+        let x = saturate(left);
+        let y = saturate(top);
+        Self::with(
+            x,
+            y,
+            saturate(right - i64::from(x)),
+            saturate(bottom - i64::from(y)),
+        )
+    }
+
+    /// Returns the x-position of the left edge.
+    pub fn left(&self) -> i64 {
+        i64::from(self.x)
+    }
+
+    /// Returns the y-position of the top edge.
+    pub fn top(&self) -> i64 {
+        i64::from(self.y)
+    }
+
+    /// Returns the x-position of the right edge.
+    ///
+    /// # Note
+    ///
+    /// The edge is exclusive and calculated using 64 bit integers, so it can
+    /// lie outside of the `i32` range.
+    pub fn right(&self) -> i64 {
+        i64::from(self.x) + i64::from(self.width)
+    }
+
+    /// Returns the y-position of the bottom edge.
+    ///
+    /// # Note
+    ///
+    /// The edge is exclusive and calculated using 64 bit integers, so it can
+    /// lie outside of the `i32` range.
+    pub fn bottom(&self) -> i64 {
+        i64::from(self.y) + i64::from(self.height)
+    }
+
+    /// Returns the center of the bounding box, rounded towards the top left.
+    pub fn center(&self) -> (i64, i64) {
+        (
+            (self.left() + self.right()).div_euclid(2),
+            (self.top() + self.bottom()).div_euclid(2),
+        )
+    }
+
+    /// Returns the surface area of the bounding box. Negative widths and
+    /// heights result in an area of zero.
+    pub fn area(&self) -> i64 {
+        i64::from(self.width.max(0)) * i64::from(self.height.max(0))
+    }
+
+    /// Returns `true` if the bounding box has no surface area.
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    /// Returns `true` if the given point lies inside of the bounding box.
+    ///
+    /// # Arguments
+    ///
+    /// - `x`: The x-position of the point.
+    /// - `y`: The y-position of the point.
+    ///
+    /// # Note
+    ///
+    /// Points on the right and bottom edges are not part of the bounding box.
+    pub fn contains_point(&self, x: i32, y: i32) -> bool {
+        let (x, y) = (i64::from(x), i64::from(y));
+        self.left() <= x && x < self.right() && self.top() <= y && y < self.bottom()
+    }
+
+    /// Returns `true` if the other bounding box lies completely inside of this
+    /// one. Empty bounding boxes are contained if they lie within the edges.
+    pub fn contains(&self, other: BBox) -> bool {
+        self.left() <= other.left()
+            && self.top() <= other.top()
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    /// Returns the area that is covered by both bounding boxes.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the bounding boxes do not overlap, e.g. because they only
+    /// touch each other or because one of them is empty.
+    pub fn intersection(&self, other: BBox) -> Option<BBox> {
+        let bbox = BBox::from_edges(
+            self.left().max(other.left()),
+            self.top().max(other.top()),
+            self.right().min(other.right()),
+            self.bottom().min(other.bottom()),
+        );
+        if bbox.is_empty() {
+            None
+        } else {
+            Some(bbox)
+        }
+    }
+
+    /// Returns the smallest bounding box that contains both bounding boxes.
+    ///
+    /// # Note
+    ///
+    /// Empty bounding boxes are ignored. If both are empty, this bounding box
+    /// is returned unchanged.
+    pub fn union(&self, other: BBox) -> BBox {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return other;
+        }
+        BBox::from_edges(
+            self.left().min(other.left()),
+            self.top().min(other.top()),
+            self.right().max(other.right()),
+            self.bottom().max(other.bottom()),
+        )
+    }
+
+    /// Returns the bounding box moved by the given offsets. The position
+    /// saturates at the bounds of `i32`.
+    ///
+    /// # Arguments
+    ///
+    /// - `dx`: The offset on the x-axis.
+    /// - `dy`: The offset on the y-axis.
+    pub fn translate(&self, dx: i32, dy: i32) -> BBox {
+        BBox::with(
+            self.x.saturating_add(dx),
+            self.y.saturating_add(dy),
+            self.width,
+            self.height,
+        )
+    }
+
+    /// Returns the bounding box shrunk by the given margins on each edge.
+    ///
+    /// # Note
+    ///
+    /// The width and height never become negative. If the margins are bigger
+    /// than the bounding box, the result is empty and starts at the shifted
+    /// left and top edges.
+    pub fn inset(&self, margins: Margins) -> BBox {
+        let left = self.left() + i64::from(margins.left);
+        let top = self.top() + i64::from(margins.top);
+        BBox::from_edges(
+            left,
+            top,
+            (self.right() - i64::from(margins.right)).max(left),
+            (self.bottom() - i64::from(margins.bottom)).max(top),
+        )
+    }
+
+    /// Returns the bounding box grown by the given margins on each edge.
+    ///
+    /// # Note
+    ///
+    /// This is the same as insetting the bounding box by the negated margins.
+    pub fn outset(&self, margins: Margins) -> BBox {
+        let left = self.left() - i64::from(margins.left);
+        let top = self.top() - i64::from(margins.top);
+        BBox::from_edges(
+            left,
+            top,
+            (self.right() + i64::from(margins.right)).max(left),
+            (self.bottom() + i64::from(margins.bottom)).max(top),
+        )
+    }
+
+    /// Returns the areas of this bounding box that are not covered by the
+    /// other one.
+    ///
+    /// # Returns
+    ///
+    /// Up to four non-overlapping bounding boxes: one spanning the whole width
+    /// above and below the other bounding box each, and one to the left and
+    /// right of it. If the bounding boxes do not overlap, this bounding box is
+    /// returned as the only element. If the other bounding box covers this one
+    /// completely, the returned `Vec` is empty.
+    pub fn subtract(&self, other: BBox) -> Vec<BBox> {
+        let cut = match self.intersection(other) {
+            Some(cut) => cut,
+            None if self.is_empty() => return Vec::with_capacity(0),
+            None => return vec![*self],
+        };
+
+        let remains = [
+            BBox::from_edges(self.left(), self.top(), self.right(), cut.top()),
+            BBox::from_edges(self.left(), cut.bottom(), self.right(), self.bottom()),
+            BBox::from_edges(self.left(), cut.top(), cut.left(), cut.bottom()),
+            BBox::from_edges(cut.right(), cut.top(), self.right(), cut.bottom()),
+        ];
+        remains
+            .iter()
+            .copied()
+            .filter(|bbox| !bbox.is_empty())
+            .collect()
+    }
+}
+
+// Converts a value to `i32`, saturating at its bounds.
+fn saturate(value: i64) -> i32 {
+    value
+        .max(i64::from(i32::min_value()))
+        .min(i64::from(i32::max_value())) as i32
+}
+
+// Places bounding boxes of the given sizes next to each other along the split
+// direction, starting at the root's origin.
+fn split_along<I>(root: BBox, sizes: I, split_direction: SplitDirection) -> Vec<BBox>
+where
+    I: IntoIterator<Item = i32>,
+{
+    let mut offset = 0;
+    sizes
+        .into_iter()
+        .map(|size| {
+            let bbox = match split_direction {
+                SplitDirection::Horizontal => {
+                    BBox::with(root.x, saturate(root.top() + offset), root.width, size)
+                }
+                SplitDirection::Vertical => {
+                    BBox::with(saturate(root.left() + offset), root.y, size, root.height)
+                }
+            };
+            offset += i64::from(size);
+            bbox
+        })
+        .collect()
+}
+
+// Returns the share of `length` given by `ratio`, rounded to the nearest pixel.
+fn scale(length: i32, ratio: f64) -> i32 {
+    let ratio = if ratio.is_nan() {
+        0.5
+    } else {
+        ratio.max(0.0).min(1.0)
+    };
+    (f64::from(length) * ratio).round() as i32
+}
+
+// Distributes `length` pixels between parts according to their weights using
+// the largest remainder method. The returned sizes always add up to `length`
+// (or zero for negative lengths).
+fn distribute(length: i32, weights: &[f64]) -> Vec<i32> {
+    if weights.is_empty() {
+        return Vec::with_capacity(0);
+    }
+
+    let length = length.max(0);
+    let mut weights: Vec<f64> = weights
+        .iter()
+        .map(|&weight| {
+            if weight.is_finite() && weight > 0.0 {
+                weight
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let mut sum: f64 = weights.iter().sum();
+    if !(sum > 0.0 && sum.is_finite()) {
+        weights = vec![1.0; weights.len()];
+        sum = weights.len() as f64;
+    }
+
+    let shares: Vec<f64> = weights
+        .iter()
+        .map(|weight| f64::from(length) * weight / sum)
+        .collect();
+    let mut sizes: Vec<i32> = shares.iter().map(|share| share.floor() as i32).collect();
+
+    // Parts with the biggest fractional share receive the left over pixels
+    // first. Ties are resolved in favor of earlier parts.
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by(|&a, &b| {
+        let fraction_a = shares[a] - shares[a].floor();
+        let fraction_b = shares[b] - shares[b].floor();
+        fraction_b
+            .partial_cmp(&fraction_a)
+            .unwrap_or(Ordering::Equal)
+            .then(a.cmp(&b))
+    });
+
+    let mut remainder = length - sizes.iter().sum::<i32>();
+    for &index in order.iter().cycle() {
+        match remainder.cmp(&0) {
+            Ordering::Greater => {
+                sizes[index] += 1;
+                remainder -= 1;
+            }
+            // Floating point errors might hand out a pixel too much.
+            Ordering::Less if sizes[index] > 0 => {
+                sizes[index] -= 1;
+                remainder += 1;
+            }
+            Ordering::Less => {}
+            Ordering::Equal => break,
+        }
+    }
+
+    sizes
 }
 
 impl fmt::Display for BBox {
@@ -215,6 +605,61 @@ impl Arbitrary for BBox {
     }
 }
 
+#[cfg(test)]
+impl Arbitrary for Margins {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        Margins::with(
+            i32::arbitrary(g),
+            i32::arbitrary(g),
+            i32::arbitrary(g),
+            i32::arbitrary(g),
+        )
+    }
+}
+
+/// Per-edge values, e.g. used to shrink a bounding box from each side.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Margins {
+    /// The value for the top edge.
+    pub top: i32,
+    /// The value for the right edge.
+    pub right: i32,
+    /// The value for the bottom edge.
+    pub bottom: i32,
+    /// The value for the left edge.
+    pub left: i32,
+}
+
+impl Margins {
+    /// Creates empty margins.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates margins with specific values.
+    ///
+    /// # Arguments
+    ///
+    /// - `top`: The value for the top edge.
+    /// - `right`: The value for the right edge.
+    /// - `bottom`: The value for the bottom edge.
+    /// - `left`: The value for the left edge.
+    pub fn with(top: i32, right: i32, bottom: i32, left: i32) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// Creates margins that use the same value for all edges.
+    pub fn uniform(value: i32) -> Self {
+        Self::with(value, value, value, value)
+    }
+}
+
 /// A builder for creating bounding boxes.
 #[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct BBoxBuilder {
@@ -284,6 +729,7 @@ impl Default for SplitDirection {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use quickcheck_macros::quickcheck;
 
     #[test]
     fn test_horizontal_split() {
@@ -308,4 +754,256 @@ mod tests {
 
         assert_eq!(expected, splitted);
     }
+
+    #[test]
+    fn test_horizontal_split_by() {
+        let bbox = BBox::with(0, 100, 1920, 1000);
+        let splitted = bbox.horizontal_split_by(0.62);
+
+        let upper = BBox::with(0, 100, 1920, 620);
+        let lower = BBox::with(0, 720, 1920, 380);
+        let expected = HorizontalSplit::with(upper, lower);
+
+        assert_eq!(expected, splitted);
+    }
+
+    #[test]
+    fn test_vertical_split_by() {
+        let bbox = BBox::with(1920, 0, 1920, 1080);
+        let splitted = bbox.vertical_split_by(0.25);
+
+        let left = BBox::with(1920, 0, 480, 1080);
+        let right = BBox::with(2400, 0, 1440, 1080);
+        let expected = VerticalSplit::with(left, right);
+
+        assert_eq!(expected, splitted);
+    }
+
+    #[test]
+    fn test_equal_split() {
+        let bbox = BBox::with(1920, 100, 1000, 980);
+        let bboxes = BBox::equal_split(bbox, 3, SplitDirection::Horizontal);
+
+        let expected = vec![
+            BBox::with(1920, 100, 1000, 327),
+            BBox::with(1920, 427, 1000, 327),
+            BBox::with(1920, 754, 1000, 326),
+        ];
+
+        assert_eq!(expected, bboxes);
+    }
+
+    #[test]
+    fn test_equal_split_with_more_parts_than_pixels() {
+        let bbox = BBox::with(10, 0, 2, 100);
+        let bboxes = BBox::equal_split(bbox, 3, SplitDirection::Vertical);
+
+        let expected = vec![
+            BBox::with(10, 0, 1, 100),
+            BBox::with(11, 0, 1, 100),
+            BBox::with(12, 0, 0, 100),
+        ];
+
+        assert_eq!(expected, bboxes);
+    }
+
+    #[test]
+    fn test_split_does_not_overflow() {
+        let max = i32::max_value();
+        let bbox = BBox::with(max - 1, 0, 3, 10);
+        let bboxes = BBox::equal_split(bbox, 3, SplitDirection::Vertical);
+
+        let expected = vec![
+            BBox::with(max - 1, 0, 1, 10),
+            BBox::with(max, 0, 1, 10),
+            BBox::with(max, 0, 1, 10),
+        ];
+        assert_eq!(expected, bboxes);
+    }
+
+    #[test]
+    fn test_weighted_split() {
+        let bbox = BBox::with(10, 20, 100, 50);
+        let bboxes = BBox::weighted_split(bbox, &[1.0, 1.0, 1.0], SplitDirection::Vertical);
+
+        let expected = vec![
+            BBox::with(10, 20, 34, 50),
+            BBox::with(44, 20, 33, 50),
+            BBox::with(77, 20, 33, 50),
+        ];
+
+        assert_eq!(expected, bboxes);
+    }
+
+    #[test]
+    fn test_weighted_split_without_positive_weights() {
+        let bbox = BBox::with(0, 0, 100, 100);
+        let bboxes = BBox::weighted_split(bbox, &[0.0, -1.0], SplitDirection::Horizontal);
+
+        let expected = vec![BBox::with(0, 0, 100, 50), BBox::with(0, 50, 100, 50)];
+
+        assert_eq!(expected, bboxes);
+    }
+
+    #[quickcheck]
+    fn prop_weighted_split_covers_root(root: BBox, weights: Vec<u8>) -> bool {
+        let weights: Vec<f64> = weights.into_iter().map(f64::from).collect();
+        let bboxes = BBox::weighted_split(root, &weights, SplitDirection::Vertical);
+
+        let mut x = root.x;
+        for bbox in &bboxes {
+            if bbox.x != x || bbox.y != root.y || bbox.height != root.height {
+                return false;
+            }
+            x += bbox.width;
+        }
+
+        bboxes.len() == weights.len() && (bboxes.is_empty() || x == root.x + root.width)
+    }
+
+    #[quickcheck]
+    fn prop_equal_split_covers_root(root: BBox, number_of_bboxes: u8) -> bool {
+        let number_of_bboxes = usize::from(number_of_bboxes);
+        let bboxes = BBox::equal_split(root, number_of_bboxes, SplitDirection::Horizontal);
+
+        let mut y = root.y;
+        for bbox in &bboxes {
+            if bbox.y != y || bbox.x != root.x || bbox.width != root.width {
+                return false;
+            }
+            y += bbox.height;
+        }
+
+        bboxes.len() == number_of_bboxes && (bboxes.is_empty() || y == root.y + root.height)
+    }
+
+    #[quickcheck]
+    fn prop_equal_split_does_not_overlap(root: BBox, number_of_bboxes: u8) -> bool {
+        let bboxes = BBox::equal_split(
+            root,
+            usize::from(number_of_bboxes),
+            SplitDirection::Vertical,
+        );
+
+        let sizes_are_equal = bboxes
+            .iter()
+            .all(|bbox| bbox.width >= 0 && (bbox.width - bboxes[0].width).abs() <= 1);
+        let parts_are_disjoint = bboxes
+            .windows(2)
+            .all(|pair| pair[0].x + pair[0].width <= pair[1].x);
+
+        sizes_are_equal && parts_are_disjoint
+    }
+
+    #[test]
+    fn test_intersection_and_union() {
+        let a = BBox::with(0, 0, 100, 100);
+        let b = BBox::with(50, 80, 100, 100);
+
+        assert_eq!(Some(BBox::with(50, 80, 50, 20)), a.intersection(b));
+        assert_eq!(BBox::with(0, 0, 150, 180), a.union(b));
+        assert_eq!(None, a.intersection(BBox::with(100, 0, 10, 10)));
+        assert_eq!(a, a.union(BBox::new()));
+    }
+
+    #[test]
+    fn test_containment() {
+        let bbox = BBox::with(10, 10, 100, 50);
+
+        assert!(bbox.contains_point(10, 10));
+        assert!(!bbox.contains_point(110, 10));
+        assert!(bbox.contains(BBox::with(60, 20, 50, 40)));
+        assert!(!bbox.contains(BBox::with(60, 20, 51, 40)));
+        assert_eq!((60, 35), bbox.center());
+        assert_eq!(5000, bbox.area());
+    }
+
+    #[test]
+    fn test_inset_and_outset() {
+        let bbox = BBox::with(0, 0, 1920, 1080);
+        let margins = Margins::with(30, 10, 10, 10);
+
+        assert_eq!(BBox::with(10, 30, 1900, 1040), bbox.inset(margins));
+        assert_eq!(BBox::with(-10, -30, 1940, 1120), bbox.outset(margins));
+        assert_eq!(
+            BBox::with(5, 5, 0, 0),
+            BBox::with(0, 0, 8, 8).inset(Margins::uniform(5))
+        );
+    }
+
+    #[test]
+    fn test_subtract() {
+        let bbox = BBox::with(0, 0, 100, 100);
+
+        let expected = vec![
+            BBox::with(0, 0, 100, 20),
+            BBox::with(0, 70, 100, 30),
+            BBox::with(0, 20, 10, 50),
+            BBox::with(60, 20, 40, 50),
+        ];
+        assert_eq!(expected, bbox.subtract(BBox::with(10, 20, 50, 50)));
+        assert_eq!(vec![bbox], bbox.subtract(BBox::with(100, 0, 10, 10)));
+        assert_eq!(
+            Vec::<BBox>::new(),
+            bbox.subtract(BBox::with(-10, -10, 200, 200))
+        );
+    }
+
+    #[test]
+    fn test_geometry_does_not_overflow() {
+        let max = i32::max_value();
+        let bbox = BBox::with(max - 10, max - 10, max, max);
+
+        assert_eq!(i64::from(max) * 2 - 10, bbox.right());
+        assert_eq!(BBox::with(max, max - 10, max, max), bbox.translate(max, 0));
+        assert_eq!(
+            BBox::with(i32::min_value(), max - 10, max, max),
+            BBox::with(i32::min_value() + 5, max - 10, max, max).outset(Margins::with(0, 0, 0, 10))
+        );
+        assert_eq!(
+            Some(BBox::with(max - 10, max - 10, 10, 10)),
+            bbox.intersection(BBox::with(0, 0, max, max))
+        );
+    }
+
+    #[quickcheck]
+    fn prop_intersection_is_contained(a: BBox, b: BBox) -> bool {
+        match a.intersection(b) {
+            Some(bbox) => a.contains(bbox) && b.contains(bbox) && !bbox.is_empty(),
+            None => a.is_empty() || b.is_empty() || a.subtract(b) == vec![a],
+        }
+    }
+
+    #[quickcheck]
+    fn prop_union_contains_both(a: BBox, b: BBox) -> bool {
+        let union = a.union(b);
+        (a.is_empty() || union.contains(a)) && (b.is_empty() || union.contains(b))
+    }
+
+    #[quickcheck]
+    fn prop_inset_and_outset_are_inverse(bbox: BBox, margins: Margins) -> bool {
+        let margins = Margins::with(
+            margins.top.abs(),
+            margins.right.abs(),
+            margins.bottom.abs(),
+            margins.left.abs(),
+        );
+        let inset = bbox.inset(margins);
+        bbox.outset(margins).inset(margins) == bbox && inset.width >= 0 && inset.height >= 0
+    }
+
+    #[quickcheck]
+    fn prop_subtract_covers_remaining_area(a: BBox, b: BBox) -> bool {
+        let remains = a.subtract(b);
+        let covered = a.intersection(b).map(|bbox| bbox.area()).unwrap_or(0);
+
+        let disjoint = remains.iter().enumerate().all(|(i, x)| {
+            a.contains(*x)
+                && x.intersection(b).is_none()
+                && remains[i + 1..]
+                    .iter()
+                    .all(|y| x.intersection(*y).is_none())
+        });
+        disjoint && remains.iter().map(BBox::area).sum::<i64>() == a.area() - covered
+    }
 }
@@ -6,10 +6,10 @@
 //! - `toml`
 //! - `yaml`
 
-use anyhow::{anyhow, Result};
+use crate::error::{Error, Location, Result};
 use lazy_static::lazy_static;
 use serde::de::DeserializeOwned;
-use std::{fs::File, io::Read, path::Path};
+use std::path::Path;
 
 lazy_static! {
     /// Contains all currently supported configuration file types.
@@ -49,14 +49,15 @@ lazy_static! {
 ///
 /// The parsed config file. It's up to the user to denote the type of the config
 /// file.
+///
+/// An error is returned if the format of the file is not supported, if the
+/// file could not be read or if it could not be parsed.
 pub fn parse<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T> {
+    let path = path.as_ref();
     let file_extension = path
-        .as_ref()
         .extension()
-        .ok_or_else(|| anyhow!("Failed to retrieve config file extension"))?
-        .to_str()
-        .ok_or_else(|| anyhow!("Failed to convert config file extension to str"))?;
-    println!("extension: {}", &file_extension);
+        .and_then(|extension| extension.to_str())
+        .ok_or_else(|| Error::UnsupportedFormat(path.to_path_buf()))?;
 
     match file_extension {
         #[cfg(feature = "config-json")]
@@ -74,43 +75,140 @@ pub fn parse<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T> {
         #[cfg(feature = "config-yaml")]
         "yaml" | "yml" => parse_yaml(path),
 
-        _ => unreachable!(),
+        _ => Err(Error::UnsupportedFormat(path.to_path_buf())),
+    }
+}
+
+// Reads the content of the config file.
+#[cfg(any(
+    feature = "config-json",
+    feature = "config-json5",
+    feature = "config-ron",
+    feature = "config-toml",
+    feature = "config-yaml"
+))]
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|err| Error::Io {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+// Creates a parse error.
+#[cfg(any(
+    feature = "config-json",
+    feature = "config-json5",
+    feature = "config-ron",
+    feature = "config-toml",
+    feature = "config-yaml"
+))]
+fn parse_error(path: &Path, message: impl ToString, location: Option<Location>) -> Error {
+    Error::Parse {
+        path: path.to_path_buf(),
+        message: message.to_string(),
+        location,
     }
 }
 
 #[cfg(feature = "config-json")]
-fn parse_json<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T> {
-    let file = File::open(path.as_ref())?;
-    Ok(serde_json::from_reader(file)?)
+fn parse_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    serde_json::from_str(&read(path)?).map_err(|err| {
+        let location = Location {
+            line: err.line(),
+            column: err.column(),
+        };
+        parse_error(path, &err, Some(location))
+    })
 }
 
 #[cfg(feature = "config-json5")]
-fn parse_json5<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T> {
-    let file = File::open(path.as_ref())?;
-    Ok(serde_json::from_reader(file)?)
+fn parse_json5<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    json5::from_str(&read(path)?).map_err(|err| parse_error(path, err, None))
 }
 
 #[cfg(feature = "config-ron")]
-fn parse_ron<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T> {
-    let mut file = File::open(path.as_ref())?;
-    let mut content = String::new();
-
-    file.read_to_string(&mut content)?;
-    Ok(ron::from_str(&content)?)
+fn parse_ron<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    ron::from_str(&read(path)?).map_err(|err: ron::Error| {
+        // ron reports a position of 0:0 if the location is unknown.
+        let location = if err.position.line == 0 {
+            None
+        } else {
+            Some(Location {
+                line: err.position.line,
+                column: err.position.col,
+            })
+        };
+        parse_error(path, &err.code, location)
+    })
 }
 
 #[cfg(feature = "config-toml")]
-fn parse_toml<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T> {
-    let mut file = File::open(path.as_ref())?;
-    let mut content = String::new();
-
-    file.read_to_string(&mut content)?;
-    println!("toml");
-    Ok(toml::from_str(&content)?)
+fn parse_toml<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    toml::from_str(&read(path)?).map_err(|err| {
+        // The line and column of toml errors are zero-based.
+        let location = err.line_col().map(|(line, column)| Location {
+            line: line + 1,
+            column: column + 1,
+        });
+        parse_error(path, err, location)
+    })
 }
 
 #[cfg(feature = "config-yaml")]
-fn parse_yaml<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T> {
-    let file = File::open(path.as_ref())?;
-    Ok(serde_yaml::from_reader(file)?)
+fn parse_yaml<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    serde_yaml::from_str(&read(path)?).map_err(|err| {
+        // The columns of yaml errors are zero-based.
+        let location = err.location().map(|location| Location {
+            line: location.line(),
+            column: location.column() + 1,
+        });
+        parse_error(path, err, location)
+    })
+}
+
+#[cfg(all(test, feature = "config-toml"))]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_unsupported_format() {
+        let result: Result<Config> = parse("config.unknown");
+        assert_eq!(
+            Err(Error::UnsupportedFormat("config.unknown".into())),
+            result
+        );
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let path = std::env::temp_dir().join("twm-test-parse-error-location.toml");
+        std::fs::write(&path, "[gaps]\ninner = \"ten\"\n").unwrap();
+        let result: Result<Config> = parse(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        match result {
+            Err(Error::Parse { location, .. }) => {
+                assert_eq!(2, location.unwrap().line);
+            }
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "config-ron")]
+    #[test]
+    fn test_parse_ron_error_location() {
+        let path = std::env::temp_dir().join("twm-test-parse-ron-error-location.ron");
+        std::fs::write(&path, "(\n    gaps: (inner: \"ten\"),\n)\n").unwrap();
+        let result: Result<Config> = parse(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        match result {
+            Err(Error::Parse { location, .. }) => {
+                assert_eq!(2, location.unwrap().line);
+            }
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
 }
@@ -1,10 +1,16 @@
 //!
 
-use crate::debug::workspace_path;
+use crate::{
+    debug::workspace_path,
+    display::DisplayId,
+    error::{Error, Result},
+    layout::gaps::Gaps,
+};
 use directories_next::ProjectDirs;
 use file_types::CONFIG_FILE_EXTENSIONS;
 use lazy_static::lazy_static;
 use log::info;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 mod file_types;
@@ -13,57 +19,173 @@ use std::cmp::Ordering;
 
 lazy_static! {
     /// One-time calculation of the config file path.
-    static ref CONFIG_FILE_PATH: Option<PathBuf> = init_config_file_path();
+    static ref CONFIG_FILE_PATH: Result<PathBuf> = init_config_file_path();
 }
 
 #[cfg(feature = "config-toml")]
 pub use toml;
 
+/// The configuration of the window manager.
+///
+/// All fields are optional inside of the config file and fall back to their
+/// default values.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// The gaps applied by all layouts.
+    pub gaps: Gaps,
+    /// The configuration of named workspaces.
+    pub workspaces: Vec<WorkspaceConfig>,
+}
+
+impl Config {
+    /// Returns the configuration of the workspace with the given name.
+    ///
+    /// # Returns
+    ///
+    /// `Some(&WorkspaceConfig)` if the workspace is configured, `None`
+    /// otherwise.
+    pub fn workspace(&self, name: &str) -> Option<&WorkspaceConfig> {
+        self.workspaces
+            .iter()
+            .find(|workspace| workspace.name == name)
+    }
+}
+
+/// The configuration of a named workspace.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(default)]
+pub struct WorkspaceConfig {
+    /// The name of the workspace.
+    pub name: String,
+    /// An optional label, e.g. an icon, that is shown instead of the name.
+    pub label: Option<String>,
+    /// The id of the display the workspace is created on. If `None`, the
+    /// workspace is created on the focused display.
+    pub display: Option<DisplayId>,
+    /// Whether the workspace is created on startup and kept when it is empty.
+    pub persistent: bool,
+}
+
 /// Returns the config file path.
 ///
+/// # Returns
+///
+/// An error if no config file or more than one config file has been found.
+///
 /// # Note
 ///
 /// The value is only calculated once the first time this method is called.
-pub fn config_file_path() -> Option<&'static PathBuf> {
-    (*CONFIG_FILE_PATH).as_ref()
+pub fn config_file_path() -> Result<&'static PathBuf> {
+    (*CONFIG_FILE_PATH).as_ref().map_err(Clone::clone)
 }
 
 // Initializes the config file path.
-fn init_config_file_path() -> Option<PathBuf> {
-    if let Some(project_dirs) = ProjectDirs::from("net", "zerotask", "twm") {
-        let config_dir = project_dirs.config_dir();
-
-        // In the case the build is running in debug mode, a special config file is used
-        // for faster iterations.
-        if cfg!(debug_assertions) {
-            println!("Running in debug, using debug configuration file");
-            return Some(workspace_path().join("config.debug.toml"));
-        }
+fn init_config_file_path() -> Result<PathBuf> {
+    let project_dirs = ProjectDirs::from("net", "zerotask", "twm").ok_or(Error::NoConfig)?;
+    let config_dir = project_dirs.config_dir();
+
+    // In the case the build is running in debug mode, a special config file is used
+    // for faster iterations.
+    if cfg!(debug_assertions) {
+        println!("Running in debug, using debug configuration file");
+        return Ok(workspace_path().join("config.debug.toml"));
+    }
 
-        let mut config_files = Vec::new();
+    let mut config_files = Vec::new();
 
-        for file_extension in &*CONFIG_FILE_EXTENSIONS {
-            let config_file_path = config_dir.join(format!("config.{}", file_extension));
-            if config_file_path.exists() {
-                config_files.push(config_file_path);
-            }
+    for file_extension in &*CONFIG_FILE_EXTENSIONS {
+        let config_file_path = config_dir.join(format!("config.{}", file_extension));
+        if config_file_path.exists() {
+            config_files.push(config_file_path);
         }
+    }
+
+    select_config_file(config_files)
+}
 
-        match config_files.len().cmp(&1) {
-            Ordering::Equal => {
-                let path = config_files.pop();
-                info!("Using config file at {}", path.as_ref().unwrap().display());
-                return path;
-            }
-            Ordering::Greater => panic!(
-                "Only one config file is allowed, found {}",
-                config_files.len()
-            ),
-            Ordering::Less => {
-                panic!("No configuration file found!");
-            }
+// Selects the config file out of all found ones.
+fn select_config_file(mut config_files: Vec<PathBuf>) -> Result<PathBuf> {
+    match config_files.len().cmp(&1) {
+        Ordering::Equal => {
+            let path = config_files.pop().ok_or(Error::NoConfig)?;
+            info!("Using config file at {}", path.display());
+            Ok(path)
         }
+        Ordering::Greater => Err(Error::AmbiguousConfig(config_files)),
+        Ordering::Less => Err(Error::NoConfig),
     }
+}
+
+#[cfg(all(test, feature = "config-toml"))]
+mod tests {
+    use super::*;
+    use crate::bbox::Margins;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_gaps() {
+        let config: Config = toml::from_str(
+            r#"
+            [gaps]
+            inner = 10
+
+            [gaps.outer]
+            top = 30
+            left = 5
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(Gaps::with(10, Margins::with(30, 0, 0, 5)), config.gaps);
+    }
+
+    #[test]
+    fn test_parse_workspaces() {
+        let config: Config = toml::from_str(
+            r#"
+            [[workspaces]]
+            name = "web"
+            label = "W"
+            display = 1
+
+            [[workspaces]]
+            name = "code"
+            persistent = true
+            "#,
+        )
+        .unwrap();
 
-    None
+        let expected = WorkspaceConfig {
+            name: "code".into(),
+            label: None,
+            display: None,
+            persistent: true,
+        };
+        assert_eq!(2, config.workspaces.len());
+        assert_eq!(Some(1), config.workspace("web").unwrap().display);
+        assert_eq!(Some(&expected), config.workspace("code"));
+    }
+
+    #[test]
+    fn test_select_config_file() {
+        assert_eq!(Err(Error::NoConfig), select_config_file(vec![]));
+
+        let paths = vec![PathBuf::from("config.toml"), PathBuf::from("config.yaml")];
+        assert_eq!(
+            Err(Error::AmbiguousConfig(paths.clone())),
+            select_config_file(paths)
+        );
+        assert_eq!(
+            Ok(PathBuf::from("config.toml")),
+            select_config_file(vec![PathBuf::from("config.toml")])
+        );
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        let config: Config = toml::from_str("").unwrap();
+
+        assert_eq!(Config::default(), config);
+    }
 }
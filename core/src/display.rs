@@ -1,19 +1,97 @@
 //!
 
-use crate::bbox::BBox;
+use crate::{
+    bbox::{BBox, Margins},
+    util::{nearest_in_direction, Direction},
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// A display id.
 pub type DisplayId = u32;
 
+/// A reservation id.
+pub type ReservationId = u32;
+
+/// An edge of a display.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Edge {
+    /// The top edge.
+    Top,
+    /// The right edge.
+    Right,
+    /// The bottom edge.
+    Bottom,
+    /// The left edge.
+    Left,
+}
+
+/// An area along an edge of a display that tiles must not cover, e.g. the
+/// space taken by a status bar or dock.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Reservation {
+    /// The edge the area is attached to.
+    pub edge: Edge,
+    /// The thickness of the area in physical pixels, measured from the edge.
+    pub size: i32,
+}
+
+impl Reservation {
+    /// Creates a reservation.
+    ///
+    /// # Arguments
+    ///
+    /// - `edge`: The edge the area is attached to.
+    /// - `size`: The thickness of the area in physical pixels, measured from
+    ///   the edge.
+    pub fn with(edge: Edge, size: i32) -> Self {
+        Self { edge, size }
+    }
+}
+
+/// The denominator of a display's fixed-point scale, e.g. a scale of `180`
+/// equals a scale factor of `1.5`.
+pub const SCALE_DENOMINATOR: u32 = 120;
+
 /// A display represents a monitor of an user. These can either be physical or
 /// virtual ones.
-#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+///
+/// The bounding box of a display is given in physical pixels. Layouts work in
+/// logical units instead, which are physical pixels divided by the display's
+/// scale factor. Logical coordinates are relative to the display's origin, so
+/// the top left corner is the same in both coordinate systems.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Display {
     /// The unique id of the display.
     pub id: DisplayId,
-    /// The bounding box of the display.
+    /// The bounding box of the display in physical pixels.
     pub bbox: BBox,
+    /// The number of physical pixels per logical unit in multiples of
+    /// `1 / SCALE_DENOMINATOR`, e.g. `240` on a HiDPI display. A scale of `0`
+    /// is treated as `SCALE_DENOMINATOR`.
+    #[serde(default = "default_scale")]
+    pub scale: u32,
+    /// The areas that tiles must not cover, by their id.
+    #[serde(default)]
+    reservations: BTreeMap<ReservationId, Reservation>,
+}
+
+impl Default for Display {
+    /// Returns an empty display with a scale factor of `1.0`.
+    fn default() -> Self {
+        Self {
+            id: 0,
+            bbox: BBox::default(),
+            scale: default_scale(),
+            reservations: BTreeMap::new(),
+        }
+    }
+}
+
+/// Returns the scale of displays that do not specify one, which equals a
+/// scale factor of `1.0`.
+fn default_scale() -> u32 {
+    SCALE_DENOMINATOR
 }
 
 impl Display {
@@ -29,6 +107,356 @@ impl Display {
     /// - `id`: The id of the display
     /// - `bbox`: The bounding box of the display.
     pub fn with(id: DisplayId, bbox: BBox) -> Self {
-        Self { id, bbox }
+        Self {
+            id,
+            bbox,
+            ..Self::default()
+        }
+    }
+
+    /// Reserves an area along an edge of the display.
+    ///
+    /// # Arguments
+    ///
+    /// - `id`: The id of the reservation.
+    /// - `reservation`: The area to reserve.
+    ///
+    /// # Returns
+    ///
+    /// The reservation that previously used the id, if any.
+    pub fn reserve(&mut self, id: ReservationId, reservation: Reservation) -> Option<Reservation> {
+        self.reservations.insert(id, reservation)
+    }
+
+    /// Releases a reserved area.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Reservation)` if the id was reserved, `None` otherwise.
+    pub fn release(&mut self, id: ReservationId) -> Option<Reservation> {
+        self.reservations.remove(&id)
+    }
+
+    /// Returns the reserved areas together with their ids, ordered by id.
+    pub fn reservations(&self) -> impl Iterator<Item = (ReservationId, Reservation)> + '_ {
+        self.reservations
+            .iter()
+            .map(|(&id, &reservation)| (id, reservation))
+    }
+
+    /// Returns the space reserved on each edge.
+    ///
+    /// # Note
+    ///
+    /// Reservations on the same edge are stacked, so their sizes add up.
+    /// Negative sizes are ignored.
+    pub fn reserved_margins(&self) -> Margins {
+        let mut margins = Margins::new();
+        for reservation in self.reservations.values() {
+            let margin = match reservation.edge {
+                Edge::Top => &mut margins.top,
+                Edge::Right => &mut margins.right,
+                Edge::Bottom => &mut margins.bottom,
+                Edge::Left => &mut margins.left,
+            };
+            *margin = margin.saturating_add(reservation.size.max(0));
+        }
+        margins
+    }
+
+    /// Returns the area of the display that tiles can be laid out in, i.e. the
+    /// bounding box without the reserved areas, in physical pixels.
+    pub fn usable_bbox(&self) -> BBox {
+        self.bbox.inset(self.reserved_margins())
+    }
+
+    /// Returns the number of physical pixels per logical unit.
+    pub fn scale_factor(&self) -> f64 {
+        f64::from(self.scale()) / f64::from(SCALE_DENOMINATOR)
+    }
+
+    /// Sets the number of physical pixels per logical unit.
+    ///
+    /// # Arguments
+    ///
+    /// - `scale_factor`: The scale factor, e.g. `2.0` on a HiDPI display. It is
+    ///   rounded to the nearest multiple of `1 / SCALE_DENOMINATOR`. Values
+    ///   that are not positive fall back to `1.0`.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        let limit = f64::from(u32::max_value());
+        let scale = (scale_factor * f64::from(SCALE_DENOMINATOR)).round();
+        self.scale = if scale.is_finite() && scale >= 1.0 {
+            scale.min(limit) as u32
+        } else {
+            SCALE_DENOMINATOR
+        };
+    }
+
+    /// Converts a bounding box from logical units to physical pixels.
+    ///
+    /// # Note
+    ///
+    /// The edges are converted and rounded instead of the size, so bounding
+    /// boxes that touch each other in logical units also touch each other in
+    /// physical pixels.
+    pub fn to_physical(&self, bbox: BBox) -> BBox {
+        self.convert(bbox, self.scale_factor())
+    }
+
+    /// Converts a bounding box from physical pixels to logical units.
+    ///
+    /// # Note
+    ///
+    /// The edges are converted and rounded instead of the size, so bounding
+    /// boxes that touch each other in physical pixels also touch each other
+    /// in logical units.
+    pub fn to_logical(&self, bbox: BBox) -> BBox {
+        self.convert(bbox, 1.0 / self.scale_factor())
+    }
+
+    // Returns the fixed-point scale, falling back to `1.0` for a zero scale.
+    fn scale(&self) -> u32 {
+        if self.scale == 0 {
+            SCALE_DENOMINATOR
+        } else {
+            self.scale
+        }
+    }
+
+    // Scales the edges of a bounding box relative to the display's origin.
+    fn convert(&self, bbox: BBox, factor: f64) -> BBox {
+        let limit = f64::from(i32::max_value());
+        let scale = |value: i64, origin: i64| {
+            let offset = ((value - origin) as f64 * factor).round();
+            origin + offset.max(-limit).min(limit) as i64
+        };
+
+        BBox::from_edges(
+            scale(bbox.left(), self.bbox.left()),
+            scale(bbox.top(), self.bbox.top()),
+            scale(bbox.right(), self.bbox.left()),
+            scale(bbox.bottom(), self.bbox.top()),
+        )
+    }
+}
+
+/// The arrangement of all displays.
+///
+/// The topology answers geometric questions about displays, e.g. which display
+/// lies next to another one or which display shows a window.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct DisplayTopology {
+    /// The displays, in the order they were added.
+    displays: Vec<Display>,
+    /// The id of the primary display, if set explicitly.
+    primary_id: Option<DisplayId>,
+}
+
+impl DisplayTopology {
+    /// Creates an empty topology.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a topology.
+    ///
+    /// # Arguments
+    ///
+    /// - `displays`: The displays. If multiple displays share an id, the last
+    ///   one is used.
+    pub fn with(displays: Vec<Display>) -> Self {
+        let mut topology = Self::new();
+        for display in displays {
+            topology.insert(display);
+        }
+        topology
+    }
+
+    /// Returns all displays, in the order they were added.
+    pub fn displays(&self) -> &[Display] {
+        &self.displays
+    }
+
+    /// Returns the display with the given id.
+    pub fn display(&self, display_id: DisplayId) -> Option<&Display> {
+        self.displays
+            .iter()
+            .find(|display| display.id == display_id)
+    }
+
+    /// Adds a display or replaces a known one with the same id.
+    ///
+    /// # Returns
+    ///
+    /// The replaced display, if any.
+    pub fn insert(&mut self, display: Display) -> Option<Display> {
+        match self
+            .displays
+            .iter_mut()
+            .find(|other| other.id == display.id)
+        {
+            Some(other) => Some(std::mem::replace(other, display)),
+            None => {
+                self.displays.push(display);
+                None
+            }
+        }
+    }
+
+    /// Removes a display. If it was the primary display, the primary display
+    /// is determined automatically again.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Display)` if the display was known, `None` otherwise.
+    pub fn remove(&mut self, display_id: DisplayId) -> Option<Display> {
+        let index = self
+            .displays
+            .iter()
+            .position(|display| display.id == display_id)?;
+        if self.primary_id == Some(display_id) {
+            self.primary_id = None;
+        }
+        Some(self.displays.remove(index))
+    }
+
+    /// Returns the primary display.
+    ///
+    /// Unless set explicitly, the primary display is the one containing the
+    /// origin of the global coordinate system, or the first display if none
+    /// does.
+    pub fn primary(&self) -> Option<&Display> {
+        self.primary_id
+            .and_then(|display_id| self.display(display_id))
+            .or_else(|| self.display_containing_point(0, 0))
+            .or_else(|| self.displays.first())
+    }
+
+    /// Makes a display the primary one.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the display is known, `false` otherwise.
+    pub fn set_primary(&mut self, display_id: DisplayId) -> bool {
+        if self.display(display_id).is_none() {
+            return false;
+        }
+        self.primary_id = Some(display_id);
+        true
+    }
+
+    /// Returns the display that lies next to a display in the given direction.
+    ///
+    /// # Arguments
+    ///
+    /// - `display_id`: The id of the display to start from.
+    /// - `direction`: The direction to look in.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the display is unknown or if no display lies in the given
+    /// direction.
+    pub fn neighbor(&self, display_id: DisplayId, direction: Direction) -> Option<&Display> {
+        let origin = self.display(display_id)?.bbox;
+        let candidates = self
+            .displays
+            .iter()
+            .filter(|display| display.id != display_id)
+            .map(|display| (display, display.bbox));
+        nearest_in_direction(origin, direction, candidates)
+    }
+
+    /// Returns the display that contains the given point.
+    pub fn display_containing_point(&self, x: i32, y: i32) -> Option<&Display> {
+        self.displays
+            .iter()
+            .find(|display| display.bbox.contains_point(x, y))
+    }
+
+    /// Returns the display that contains the biggest part of a bounding box,
+    /// e.g. the original bounding box of a window.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the bounding box does not overlap any display. If multiple
+    /// displays contain equally big parts, the first one is returned.
+    pub fn display_containing(&self, bbox: BBox) -> Option<&Display> {
+        let mut best: Option<(&Display, i64)> = None;
+        for display in &self.displays {
+            let area = match display.bbox.intersection(bbox) {
+                Some(intersection) => intersection.area(),
+                None => continue,
+            };
+            if best.map_or(true, |(_, best_area)| area > best_area) {
+                best = Some((display, area));
+            }
+        }
+        best.map(|(display, _)| display)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_usable_bbox() {
+        let mut display = Display::with(0, BBox::with(1920, 0, 1920, 1080));
+        display.reserve(0, Reservation::with(Edge::Top, 20));
+        display.reserve(1, Reservation::with(Edge::Top, 10));
+        display.reserve(2, Reservation::with(Edge::Left, 48));
+        assert_eq!(BBox::with(1968, 30, 1872, 1050), display.usable_bbox());
+
+        assert_eq!(Some(Reservation::with(Edge::Top, 20)), display.release(0));
+        assert_eq!(None, display.release(0));
+        assert_eq!(BBox::with(1968, 10, 1872, 1070), display.usable_bbox());
+    }
+
+    #[test]
+    fn test_scale_factor() {
+        let mut display = Display::with(0, BBox::with(1920, 0, 2880, 1620));
+        display.set_scale_factor(1.5);
+        assert_eq!(180, display.scale);
+
+        let logical = display.to_logical(display.bbox);
+        assert_eq!(BBox::with(1920, 0, 1920, 1080), logical);
+        assert_eq!(display.bbox, display.to_physical(logical));
+
+        // Adjacent tiles stay flush, even if their sizes are rounded
+        // differently.
+        let left = display.to_physical(BBox::with(1920, 0, 641, 1080));
+        let right = display.to_physical(BBox::with(2561, 0, 1279, 1080));
+        assert_eq!(BBox::with(1920, 0, 962, 1620), left);
+        assert_eq!(left.right(), i64::from(right.x));
+        assert_eq!(display.bbox.right(), right.right());
+    }
+
+    #[test]
+    fn test_topology() {
+        let mut topology = DisplayTopology::with(vec![
+            Display::with(0, BBox::with(-1280, 56, 1280, 1024)),
+            Display::with(1, BBox::with(0, 0, 1920, 1080)),
+            Display::with(2, BBox::with(0, 1080, 1920, 1080)),
+        ]);
+
+        assert_eq!(1, topology.primary().unwrap().id);
+        assert_eq!(0, topology.neighbor(1, Direction::Left).unwrap().id);
+        assert_eq!(2, topology.neighbor(1, Direction::Down).unwrap().id);
+        assert_eq!(None, topology.neighbor(1, Direction::Right));
+        assert_eq!(0, topology.display_containing_point(-1, 56).unwrap().id);
+        assert_eq!(None, topology.display_containing_point(-1, 0));
+        assert_eq!(
+            2,
+            topology
+                .display_containing(BBox::with(100, 1000, 640, 480))
+                .unwrap()
+                .id
+        );
+
+        assert!(topology.set_primary(2));
+        assert_eq!(2, topology.primary().unwrap().id);
+        assert!(topology.remove(2).is_some());
+        assert_eq!(1, topology.primary().unwrap().id);
     }
 }
@@ -0,0 +1,156 @@
+//! Contains the error type returned by the public APIs of this crate.
+
+use crate::{tile::TileId, workspace::WorkspaceId};
+use std::{fmt, path::PathBuf};
+
+/// A result with the crate's error type.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The kind of an id that is already in use.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum IdKind {
+    /// A tile id.
+    Tile,
+    /// A window id.
+    Window,
+    /// An OS' window handle.
+    WindowHandle,
+    /// A workspace id.
+    Workspace,
+    /// A reservation id of a display area.
+    Reservation,
+}
+
+/// A location inside of a file.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Location {
+    /// The one-based line.
+    pub line: usize,
+    /// The one-based column.
+    pub column: usize,
+}
+
+/// An error of the window manager.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// A tile with the given id does not exist.
+    UnknownTile(TileId),
+    /// A workspace with the given id does not exist.
+    UnknownWorkspace(WorkspaceId),
+    /// An id is already in use.
+    DuplicateId {
+        /// The kind of the id.
+        kind: IdKind,
+        /// The id.
+        id: i64,
+    },
+    /// All ids of the given kind are in use.
+    OutOfIds(IdKind),
+    /// No config file has been found.
+    NoConfig,
+    /// Multiple config files have been found.
+    AmbiguousConfig(Vec<PathBuf>),
+    /// The format of the config file is not supported, e.g. because the
+    /// corresponding feature is disabled.
+    UnsupportedFormat(PathBuf),
+    /// The config file could not be read.
+    Io {
+        /// The path of the config file.
+        path: PathBuf,
+        /// The error message.
+        message: String,
+    },
+    /// The config file could not be parsed.
+    Parse {
+        /// The path of the config file.
+        path: PathBuf,
+        /// The error message.
+        message: String,
+        /// The location of the error, if known.
+        location: Option<Location>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownTile(id) => write!(f, "Tile {} does not exist", id),
+            Error::UnknownWorkspace(id) => write!(f, "Workspace {} does not exist", id),
+            Error::DuplicateId { kind, id } => {
+                let kind = match kind {
+                    IdKind::Tile => "Tile id",
+                    IdKind::Window => "Window id",
+                    IdKind::WindowHandle => "Window handle",
+                    IdKind::Workspace => "Workspace id",
+                    IdKind::Reservation => "Reservation id",
+                };
+                write!(f, "{} {} is already in use", kind, id)
+            }
+            Error::OutOfIds(kind) => {
+                let kind = match kind {
+                    IdKind::Tile => "tile ids",
+                    IdKind::Window => "window ids",
+                    IdKind::WindowHandle => "window handles",
+                    IdKind::Workspace => "workspace ids",
+                    IdKind::Reservation => "reservation ids",
+                };
+                write!(f, "All {} are in use", kind)
+            }
+            Error::NoConfig => write!(f, "No config file found"),
+            Error::AmbiguousConfig(paths) => {
+                write!(f, "Only one config file is allowed, found {}", paths.len())
+            }
+            Error::UnsupportedFormat(path) => {
+                write!(f, "Unsupported config file format of {}", path.display())
+            }
+            Error::Io { path, message } => {
+                write!(f, "Failed to read {}: {}", path.display(), message)
+            }
+            Error::Parse {
+                path,
+                message,
+                location: Some(location),
+            } => write!(
+                f,
+                "Failed to parse {} at {}:{}: {}",
+                path.display(),
+                location.line,
+                location.column,
+                message
+            ),
+            Error::Parse { path, message, .. } => {
+                write!(f, "Failed to parse {}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display() {
+        let error = Error::Parse {
+            path: PathBuf::from("config.toml"),
+            message: "expected a value".into(),
+            location: Some(Location { line: 3, column: 7 }),
+        };
+        assert_eq!(
+            "Failed to parse config.toml at 3:7: expected a value",
+            error.to_string()
+        );
+
+        let error = Error::DuplicateId {
+            kind: IdKind::WindowHandle,
+            id: -1,
+        };
+        assert_eq!("Window handle -1 is already in use", error.to_string());
+
+        let error = Error::OutOfIds(IdKind::Workspace);
+        assert_eq!("All workspace ids are in use", error.to_string());
+    }
+}
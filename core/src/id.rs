@@ -0,0 +1,145 @@
+//! Contains the allocation of unique ids for tiles, windows, workspaces and
+//! reserved display areas.
+
+use crate::{
+    display::ReservationId,
+    error::{Error, IdKind, Result},
+    tile::TileId,
+    window::WindowId,
+    workspace::WorkspaceId,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// Hands out unique ids for tiles, windows, workspaces and reserved display
+/// areas.
+///
+/// Ids are handed out in ascending order. Ids that are assigned elsewhere can
+/// be reserved, so the allocator never hands them out.
+///
+/// The counters are wider than the ids, so reserving the largest id only
+/// exhausts the ids instead of overflowing.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct IdAllocator {
+    /// The next tile id.
+    next_tile_id: u64,
+    /// The next window id.
+    next_window_id: u64,
+    /// The next workspace id.
+    next_workspace_id: u64,
+    /// The next reservation id.
+    next_reservation_id: u64,
+}
+
+impl IdAllocator {
+    /// Creates an allocator that starts at zero for all ids.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new, unique tile id.
+    ///
+    /// # Returns
+    ///
+    /// An error if all tile ids are in use.
+    pub fn tile_id(&mut self) -> Result<TileId> {
+        next(&mut self.next_tile_id, IdKind::Tile)
+    }
+
+    /// Returns a new, unique window id.
+    ///
+    /// # Returns
+    ///
+    /// An error if all window ids are in use.
+    pub fn window_id(&mut self) -> Result<WindowId> {
+        next(&mut self.next_window_id, IdKind::Window)
+    }
+
+    /// Returns a new, unique workspace id.
+    ///
+    /// # Returns
+    ///
+    /// An error if all workspace ids are in use.
+    pub fn workspace_id(&mut self) -> Result<WorkspaceId> {
+        next(&mut self.next_workspace_id, IdKind::Workspace)
+    }
+
+    /// Returns a new, unique reservation id.
+    ///
+    /// # Returns
+    ///
+    /// An error if all reservation ids are in use.
+    pub fn reservation_id(&mut self) -> Result<ReservationId> {
+        next(&mut self.next_reservation_id, IdKind::Reservation)
+    }
+
+    /// Reserves a tile id that is assigned elsewhere.
+    pub fn reserve_tile_id(&mut self, id: TileId) {
+        reserve(&mut self.next_tile_id, id);
+    }
+
+    /// Reserves a window id that is assigned elsewhere.
+    pub fn reserve_window_id(&mut self, id: WindowId) {
+        reserve(&mut self.next_window_id, id);
+    }
+
+    /// Reserves a workspace id that is assigned elsewhere.
+    pub fn reserve_workspace_id(&mut self, id: WorkspaceId) {
+        reserve(&mut self.next_workspace_id, id);
+    }
+
+    /// Reserves a reservation id that is assigned elsewhere.
+    pub fn reserve_reservation_id(&mut self, id: ReservationId) {
+        reserve(&mut self.next_reservation_id, id);
+    }
+}
+
+// Returns the next id and advances the counter.
+fn next(counter: &mut u64, kind: IdKind) -> Result<u32> {
+    let id = u32::try_from(*counter).map_err(|_| Error::OutOfIds(kind))?;
+    *counter += 1;
+    Ok(id)
+}
+
+// Advances the counter past the given id.
+fn reserve(counter: &mut u64, id: u32) {
+    let id = u64::from(id);
+    if id >= *counter {
+        *counter = id + 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_allocation() {
+        let mut ids = IdAllocator::new();
+        assert_eq!(Ok(0), ids.tile_id());
+        assert_eq!(Ok(1), ids.tile_id());
+        assert_eq!(Ok(0), ids.window_id());
+
+        ids.reserve_tile_id(5);
+        ids.reserve_tile_id(3);
+        assert_eq!(Ok(6), ids.tile_id());
+        ids.reserve_workspace_id(0);
+        assert_eq!(Ok(1), ids.workspace_id());
+    }
+
+    #[test]
+    fn test_out_of_ids() {
+        let mut ids = IdAllocator::new();
+        ids.reserve_window_id(u32::max_value() - 1);
+        assert_eq!(Ok(u32::max_value()), ids.window_id());
+        assert_eq!(Err(Error::OutOfIds(IdKind::Window)), ids.window_id());
+
+        ids.reserve_reservation_id(u32::max_value());
+        assert_eq!(
+            Err(Error::OutOfIds(IdKind::Reservation)),
+            ids.reservation_id()
+        );
+        assert_eq!(Ok(0), ids.tile_id());
+    }
+}
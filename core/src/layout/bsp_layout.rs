@@ -0,0 +1,625 @@
+//! A binary space partitioning layout keeps a persistent tree of splits. Each
+//! leaf of the tree holds exactly one tile.
+//!
+//! New tiles split the leaf of the focused tile, either in a direction chosen
+//! by the user or along the longer side of the focused tile. Removing a tile
+//! collapses its parent split, giving the space to the sibling node.
+
+use crate::{
+    bbox::{BBox, SplitDirection},
+    layout::{
+        clamp_ratio, Layout, LayoutMessage, LayoutMessageInfo, LayoutMeta, LayoutUpdateInfo,
+        ResizeAmount,
+    },
+    tile::TileId,
+    util::Direction,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::trace;
+
+/// A split of the space into two child nodes.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Split {
+    /// The direction of the split. Horizontal splits put the `first` node
+    /// above the `second` one, vertical splits put it to the left.
+    pub direction: SplitDirection,
+    /// The share of the space that the `first` node takes.
+    pub ratio: f64,
+    /// The first child node.
+    pub first: Node,
+    /// The second child node.
+    pub second: Node,
+}
+
+/// A node of the partition tree.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Node {
+    /// A leaf holding a tile.
+    Leaf(TileId),
+    /// A split holding two child nodes.
+    Split(Box<Split>),
+}
+
+impl Node {
+    /// Returns whether the node is a leaf holding the given tile.
+    fn is_leaf(&self, tile_id: TileId) -> bool {
+        match self {
+            Node::Leaf(id) => *id == tile_id,
+            Node::Split(_) => false,
+        }
+    }
+
+    /// Returns whether the node or one of its children holds the given tile.
+    fn contains(&self, tile_id: TileId) -> bool {
+        match self {
+            Node::Leaf(id) => *id == tile_id,
+            Node::Split(split) => split.first.contains(tile_id) || split.second.contains(tile_id),
+        }
+    }
+
+    /// Collects the tile ids of all leaves, from the first to the last one.
+    fn leaves(&self, leaves: &mut Vec<TileId>) {
+        match self {
+            Node::Leaf(id) => leaves.push(*id),
+            Node::Split(split) => {
+                split.first.leaves(leaves);
+                split.second.leaves(leaves);
+            }
+        }
+    }
+
+    /// Calculates the bounding boxes of all leaves.
+    fn bboxes(&self, bbox: BBox, bboxes: &mut HashMap<TileId, BBox>) {
+        match self {
+            Node::Leaf(id) => {
+                bboxes.insert(*id, bbox);
+            }
+            Node::Split(split) => {
+                let (first, second) = split.child_bboxes(bbox);
+                split.first.bboxes(first, bboxes);
+                split.second.bboxes(second, bboxes);
+            }
+        }
+    }
+
+    /// Replaces the leaf holding `target` with a split of the old leaf and a
+    /// new leaf holding `tile_id`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the target leaf was found, `false` otherwise.
+    fn insert(&mut self, target: TileId, tile_id: TileId, direction: SplitDirection) -> bool {
+        match self {
+            Node::Leaf(id) if *id == target => {
+                *self = Node::Split(Box::new(Split {
+                    direction,
+                    ratio: 0.5,
+                    first: Node::Leaf(target),
+                    second: Node::Leaf(tile_id),
+                }));
+                true
+            }
+            Node::Leaf(_) => false,
+            Node::Split(split) => {
+                split.first.insert(target, tile_id, direction)
+                    || split.second.insert(target, tile_id, direction)
+            }
+        }
+    }
+
+    /// Removes the leaf holding the given tile. Splits that are left with a
+    /// single child are replaced by that child.
+    ///
+    /// # Returns
+    ///
+    /// The remaining node or `None` if no leaf is left.
+    fn remove(self, tile_id: TileId) -> Option<Node> {
+        match self {
+            Node::Leaf(id) if id == tile_id => None,
+            Node::Leaf(id) => Some(Node::Leaf(id)),
+            Node::Split(split) => {
+                let split = *split;
+                match (split.first.remove(tile_id), split.second.remove(tile_id)) {
+                    (Some(first), Some(second)) => Some(Node::Split(Box::new(Split {
+                        first,
+                        second,
+                        ..split
+                    }))),
+                    (Some(node), None) | (None, Some(node)) => Some(node),
+                    (None, None) => None,
+                }
+            }
+        }
+    }
+
+    /// Returns the split that directly holds the leaf of the given tile.
+    fn parent_of_mut(&mut self, tile_id: TileId) -> Option<&mut Split> {
+        match self {
+            Node::Leaf(_) => None,
+            Node::Split(split) => {
+                if split.first.is_leaf(tile_id) || split.second.is_leaf(tile_id) {
+                    Some(split)
+                } else if split.first.contains(tile_id) {
+                    split.first.parent_of_mut(tile_id)
+                } else {
+                    split.second.parent_of_mut(tile_id)
+                }
+            }
+        }
+    }
+
+    /// Returns the path to the deepest split that holds the given tile and
+    /// whose border lies in the given direction of it, together with the
+    /// bounding box of the split. Each step of the path is `true` for the
+    /// first child and `false` for the second one.
+    fn path_towards(
+        &self,
+        tile_id: TileId,
+        direction: Direction,
+        bbox: BBox,
+    ) -> Option<(Vec<bool>, BBox)> {
+        let split = match self {
+            Node::Leaf(_) => return None,
+            Node::Split(split) => split,
+        };
+        let in_first = split.first.contains(tile_id);
+        if !in_first && !split.second.contains(tile_id) {
+            return None;
+        }
+
+        let (first, second) = split.child_bboxes(bbox);
+        let child = if in_first {
+            split.first.path_towards(tile_id, direction, first)
+        } else {
+            split.second.path_towards(tile_id, direction, second)
+        };
+        match child {
+            Some((mut path, child_bbox)) => {
+                path.insert(0, in_first);
+                Some((path, child_bbox))
+            }
+            None if split.borders(in_first, direction) => Some((Vec::new(), bbox)),
+            None => None,
+        }
+    }
+
+    /// Returns the split at the end of the given path.
+    fn split_at_mut(&mut self, path: &[bool]) -> Option<&mut Split> {
+        match (self, path.split_first()) {
+            (Node::Split(split), None) => Some(split),
+            (Node::Split(split), Some((&true, rest))) => split.first.split_at_mut(rest),
+            (Node::Split(split), Some((&false, rest))) => split.second.split_at_mut(rest),
+            (Node::Leaf(_), _) => None,
+        }
+    }
+
+    /// Rotates the node clockwise by 90 degrees.
+    fn rotate(&mut self) {
+        if let Node::Split(split) = self {
+            split.rotate();
+        }
+    }
+
+    /// Mirrors the node along the given direction.
+    fn flip(&mut self, direction: SplitDirection) {
+        if let Node::Split(split) = self {
+            split.flip(direction);
+        }
+    }
+}
+
+impl Split {
+    /// Returns the bounding boxes of both child nodes.
+    fn child_bboxes(&self, bbox: BBox) -> (BBox, BBox) {
+        match self.direction {
+            SplitDirection::Horizontal => {
+                let splitted = bbox.horizontal_split_by(self.ratio);
+                (splitted.upper(), splitted.lower())
+            }
+            SplitDirection::Vertical => {
+                let splitted = bbox.vertical_split_by(self.ratio);
+                (splitted.left(), splitted.right())
+            }
+        }
+    }
+
+    /// Returns whether the border between both child nodes lies in the given
+    /// direction of the first (or second) child node.
+    fn borders(&self, first: bool, direction: Direction) -> bool {
+        match (self.direction, direction) {
+            (SplitDirection::Vertical, Direction::Right)
+            | (SplitDirection::Horizontal, Direction::Down) => first,
+            (SplitDirection::Vertical, Direction::Left)
+            | (SplitDirection::Horizontal, Direction::Up) => !first,
+            _ => false,
+        }
+    }
+
+    /// Swaps both child nodes, keeping the size of each one.
+    fn swap(&mut self) {
+        std::mem::swap(&mut self.first, &mut self.second);
+        self.ratio = 1.0 - self.ratio;
+    }
+
+    /// Rotates the split and all of its children clockwise by 90 degrees.
+    fn rotate(&mut self) {
+        match self.direction {
+            SplitDirection::Vertical => self.direction = SplitDirection::Horizontal,
+            SplitDirection::Horizontal => {
+                self.direction = SplitDirection::Vertical;
+                self.swap();
+            }
+        }
+        self.first.rotate();
+        self.second.rotate();
+    }
+
+    /// Mirrors the split and all of its children along the given direction.
+    fn flip(&mut self, direction: SplitDirection) {
+        if self.direction == direction {
+            self.swap();
+        }
+        self.first.flip(direction);
+        self.second.flip(direction);
+    }
+}
+
+/// A binary space partitioning layout implementation.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct BspLayout {
+    /// Whether the layout is dirty or not.
+    dirty: bool,
+    /// The root of the partition tree. `None` if the layout holds no tiles.
+    root: Option<Node>,
+    /// The direction used to split the focused tile when a new tile is added.
+    /// If `None`, the focused tile is split along its longer side.
+    pub split_direction: Option<SplitDirection>,
+}
+
+impl BspLayout {
+    /// Creates a default `BspLayout`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the root of the partition tree.
+    pub fn root(&self) -> Option<&Node> {
+        self.root.as_ref()
+    }
+
+    /// Synchronizes the partition tree with the tiles of the workspace.
+    ///
+    /// Leaves of removed tiles are collapsed. New tiles split the leaf of the
+    /// most recently focused tile that is part of the tree or, if there is
+    /// none, the last leaf.
+    fn sync(&mut self, tile_ids: &[TileId], focus_history: &[TileId], boundary: BBox) {
+        let mut leaves = Vec::new();
+        if let Some(root) = &self.root {
+            root.leaves(&mut leaves);
+        }
+
+        for leaf in leaves.iter().filter(|leaf| !tile_ids.contains(leaf)) {
+            trace!("Removing leaf of tile@{}", leaf);
+            self.root = self.root.take().and_then(|root| root.remove(*leaf));
+        }
+
+        let new_tile_ids: Vec<TileId> = tile_ids
+            .iter()
+            .cloned()
+            .filter(|&id| !self.contains(id))
+            .collect();
+        let mut target = focus_history
+            .iter()
+            .cloned()
+            .find(|&id| self.contains(id))
+            .or_else(|| self.last_leaf());
+        for tile_id in new_tile_ids {
+            let split_direction = self.split_direction;
+            match (&mut self.root, target) {
+                (Some(root), Some(target)) => {
+                    let direction =
+                        split_direction.unwrap_or_else(|| longer_side(root, target, boundary));
+                    trace!("Splitting leaf of tile@{} for tile@{}", target, tile_id);
+                    root.insert(target, tile_id, direction);
+                }
+                _ => self.root = Some(Node::Leaf(tile_id)),
+            }
+            target = Some(tile_id);
+        }
+    }
+
+    /// Returns whether the partition tree holds the given tile.
+    fn contains(&self, tile_id: TileId) -> bool {
+        self.root
+            .as_ref()
+            .map_or(false, |root| root.contains(tile_id))
+    }
+
+    /// Returns the tile held by the last leaf of the partition tree.
+    fn last_leaf(&self) -> Option<TileId> {
+        let mut leaves = Vec::new();
+        if let Some(root) = &self.root {
+            root.leaves(&mut leaves);
+        }
+        leaves.pop()
+    }
+
+    // The logic behind the bsp layout.
+    #[tracing::instrument(skip(self, update_info))]
+    fn layout0<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
+        let gaps = update_info.gaps;
+        let boundary = gaps.apply_outer(update_info.workspace_bbox);
+        let tile_ids: Vec<TileId> = update_info.tiles.iter().map(|tile| tile.id).collect();
+        self.sync(&tile_ids, update_info.focus_history, boundary);
+
+        let mut bboxes = HashMap::new();
+        if let Some(root) = &self.root {
+            root.bboxes(boundary, &mut bboxes);
+        }
+        for tile in update_info.tiles.iter_mut() {
+            if let Some(bbox) = bboxes.get(&tile.id) {
+                tile.bbox = gaps.apply_inner(boundary, *bbox);
+            }
+        }
+    }
+}
+
+impl BspLayout {
+    // Moves the edge of the focused tile that faces the given direction by
+    // resizing the nearest split that borders it.
+    fn resize(
+        &mut self,
+        direction: Direction,
+        amount: ResizeAmount,
+        info: &LayoutMessageInfo,
+    ) -> bool {
+        let (root, focused_tile_id) = match (self.root.as_mut(), info.focused_tile_id) {
+            (Some(root), Some(focused_tile_id)) => (root, focused_tile_id),
+            _ => return false,
+        };
+        let boundary = info.boundary();
+        let (path, bbox) = match root.path_towards(focused_tile_id, direction, boundary) {
+            Some(found) => found,
+            None => return false,
+        };
+        let split = match root.split_at_mut(&path) {
+            Some(split) => split,
+            None => return false,
+        };
+
+        let (length, workspace_length) = match direction {
+            Direction::Left | Direction::Right => (bbox.width, boundary.width),
+            Direction::Up | Direction::Down => (bbox.height, boundary.height),
+        };
+        if length <= 0 {
+            return false;
+        }
+        let delta = amount.to_pixels(workspace_length) / f64::from(length);
+        let delta = if split.first.contains(focused_tile_id) {
+            delta
+        } else {
+            -delta
+        };
+        split.ratio = clamp_ratio(split.ratio + delta);
+
+        self.invalidate();
+        true
+    }
+}
+
+// Returns the split direction that splits the leaf of the given tile along
+// its longer side.
+fn longer_side(root: &Node, tile_id: TileId, boundary: BBox) -> SplitDirection {
+    let mut bboxes = HashMap::new();
+    root.bboxes(boundary, &mut bboxes);
+    match bboxes.get(&tile_id) {
+        Some(bbox) if bbox.height > bbox.width => SplitDirection::Horizontal,
+        _ => SplitDirection::Vertical,
+    }
+}
+
+#[typetag::serde]
+impl Layout for BspLayout {
+    fn metadata(&self) -> LayoutMeta {
+        LayoutMeta {
+            name: "BSP Layout".into(),
+        }
+    }
+
+    fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    #[tracing::instrument(skip(self, update_info))]
+    fn layout<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
+        if self.is_dirty() {
+            self.layout0(update_info);
+            self.dirty = false;
+        }
+    }
+
+    fn handle_message(&mut self, message: &LayoutMessage, info: &LayoutMessageInfo) -> bool {
+        match *message {
+            LayoutMessage::SetSplitDirection(direction) => {
+                self.split_direction = direction;
+                self.invalidate();
+                return true;
+            }
+            LayoutMessage::Resize(direction, amount) => {
+                return self.resize(direction, amount, info);
+            }
+            _ => {}
+        }
+
+        let focused_tile_id = match info.focused_tile_id {
+            Some(id) => id,
+            None => return false,
+        };
+        let parent = match self
+            .root
+            .as_mut()
+            .and_then(|root| root.parent_of_mut(focused_tile_id))
+        {
+            Some(parent) => parent,
+            None => return false,
+        };
+
+        match *message {
+            LayoutMessage::Rotate => parent.rotate(),
+            LayoutMessage::Flip(direction) => parent.flip(direction),
+            LayoutMessage::ResizeSplit(delta) => {
+                let delta = if parent.first.is_leaf(focused_tile_id) {
+                    delta
+                } else {
+                    -delta
+                };
+                parent.ratio = clamp_ratio(parent.ratio + delta);
+            }
+            _ => return false,
+        }
+
+        self.invalidate();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bbox::Margins,
+        layout::{
+            gaps::Gaps,
+            tests::{bboxes, workspace_with_tiles},
+        },
+        tile::Tile,
+        window::Window,
+        workspace::Workspace,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn tiled_workspace(number_of_tiles: usize) -> Workspace {
+        let bbox = BBox::with(0, 0, 1600, 800);
+        workspace_with_tiles(Box::new(BspLayout::new()), bbox, number_of_tiles)
+    }
+
+    #[test]
+    fn test_layout() {
+        let workspace = tiled_workspace(3);
+
+        let expected = vec![
+            BBox::with(0, 0, 800, 800),
+            BBox::with(800, 0, 400, 800),
+            BBox::with(1200, 0, 400, 800),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_remove_collapses_split() {
+        let mut workspace = tiled_workspace(3);
+
+        workspace.remove_tile_by_id(1).unwrap();
+        workspace.layout();
+
+        let expected = vec![BBox::with(0, 0, 800, 800), BBox::with(800, 0, 800, 800)];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_new_tile_splits_focused_tile() {
+        let mut workspace = tiled_workspace(3);
+        workspace.focus_tile(0);
+
+        workspace
+            .add_tile(Tile::with(3, BBox::new(), Window::new()))
+            .unwrap();
+        workspace.layout();
+
+        let expected = vec![
+            BBox::with(0, 0, 400, 800),
+            BBox::with(800, 0, 400, 800),
+            BBox::with(1200, 0, 400, 800),
+            BBox::with(400, 0, 400, 800),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_preselected_split_direction() {
+        let mut workspace = tiled_workspace(1);
+        let message = LayoutMessage::SetSplitDirection(Some(SplitDirection::Horizontal));
+        assert!(workspace.send_layout_message(&message));
+
+        workspace
+            .add_tile(Tile::with(1, BBox::new(), Window::new()))
+            .unwrap();
+        workspace.layout();
+
+        let expected = vec![BBox::with(0, 0, 1600, 400), BBox::with(0, 400, 1600, 400)];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_messages() {
+        let mut workspace = tiled_workspace(2);
+        workspace.focused_tile_id = Some(0);
+
+        assert!(workspace.send_layout_message(&LayoutMessage::ResizeSplit(0.25)));
+        workspace.layout();
+        let expected = vec![BBox::with(0, 0, 1200, 800), BBox::with(1200, 0, 400, 800)];
+        assert_eq!(expected, bboxes(&workspace));
+
+        assert!(workspace.send_layout_message(&LayoutMessage::Flip(SplitDirection::Vertical)));
+        workspace.layout();
+        let expected = vec![BBox::with(400, 0, 1200, 800), BBox::with(0, 0, 400, 800)];
+        assert_eq!(expected, bboxes(&workspace));
+
+        assert!(workspace.send_layout_message(&LayoutMessage::Rotate));
+        workspace.layout();
+        let expected = vec![BBox::with(0, 200, 1600, 600), BBox::with(0, 0, 1600, 200)];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut workspace = tiled_workspace(3);
+        workspace.focused_tile_id = Some(2);
+
+        assert!(workspace.resize_focused(Direction::Left, ResizeAmount::Pixels(100)));
+        assert!(!workspace.resize_focused(Direction::Right, ResizeAmount::Pixels(100)));
+        workspace.layout();
+        let expected = vec![
+            BBox::with(0, 0, 800, 800),
+            BBox::with(800, 0, 300, 800),
+            BBox::with(1100, 0, 500, 800),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+
+        workspace.focused_tile_id = Some(1);
+        assert!(workspace.resize_focused(Direction::Left, ResizeAmount::Percent(-12.5)));
+        workspace.layout();
+        assert_eq!(BBox::with(1000, 0, 225, 800), bboxes(&workspace)[1]);
+    }
+
+    #[test]
+    fn test_resize_with_gaps() {
+        let mut workspace = tiled_workspace(2);
+        workspace.set_gaps(Gaps::with(10, Margins::uniform(100)));
+        workspace.focused_tile_id = Some(0);
+
+        assert!(workspace.resize_focused(Direction::Right, ResizeAmount::Pixels(140)));
+        workspace.layout();
+        let expected = vec![
+            BBox::with(100, 100, 835, 600),
+            BBox::with(945, 100, 555, 600),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+}
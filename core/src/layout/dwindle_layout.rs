@@ -0,0 +1,194 @@
+//! A dwindle layout recursively splits the remaining space. Each tile takes a
+//! share of the space that is left over by the previous tiles, alternating
+//! between vertical and horizontal splits.
+//!
+//! In spiral mode, the side a tile takes rotates clockwise, resulting in a
+//! Fibonacci-like spiral.
+
+use crate::{
+    bbox::BBox,
+    layout::{
+        clamp_ratio, split_side, Layout, LayoutMessage, LayoutMessageInfo, LayoutMeta,
+        LayoutUpdateInfo,
+    },
+    util::Direction,
+};
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+/// A dwindle layout implementation.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct DwindleLayout {
+    /// Whether the layout is dirty or not.
+    dirty: bool,
+    /// The share of the remaining space that each tile takes.
+    pub split_ratio: f64,
+    /// The side that the first tile is rendered to.
+    pub start: Direction,
+    /// Whether the side rotates clockwise with each tile.
+    pub spiral: bool,
+}
+
+impl Default for DwindleLayout {
+    /// Returns a default instance that starts on the left side and splits the
+    /// remaining space in half.
+    fn default() -> Self {
+        Self {
+            dirty: false,
+            split_ratio: 0.5,
+            start: Direction::Left,
+            spiral: false,
+        }
+    }
+}
+
+impl DwindleLayout {
+    /// Creates a default `DwindleLayout`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `DwindleLayout`.
+    ///
+    /// # Arguments
+    ///
+    /// - `split_ratio`: The share of the remaining space that each tile takes.
+    ///   The value is clamped to `0.05..=0.95`.
+    /// - `start`: The side that the first tile is rendered to.
+    /// - `spiral`: Whether the side rotates clockwise with each tile.
+    pub fn with(split_ratio: f64, start: Direction, spiral: bool) -> Self {
+        Self {
+            split_ratio: clamp_ratio(split_ratio),
+            start,
+            spiral,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the side that the tile at the given index is rendered to.
+    fn side(self, index: usize) -> Direction {
+        if self.spiral {
+            (0..index % 4).fold(self.start, |side, _| side.clockwise())
+        } else if index % 2 == 0 {
+            self.start
+        } else {
+            self.start.clockwise()
+        }
+    }
+
+    /// Splits the remaining space, returning the bounding box of the tile and
+    /// the space left for the following tiles.
+    fn split(self, remaining: BBox, side: Direction) -> (BBox, BBox) {
+        split_side(remaining, side, self.split_ratio)
+    }
+
+    // The logic behind the dwindle layout.
+    #[tracing::instrument(skip(self, update_info))]
+    fn layout0<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
+        let gaps = update_info.gaps;
+        let boundary = gaps.apply_outer(update_info.workspace_bbox);
+        let tiles = &mut update_info.tiles;
+        trace!("Aligning {} tiles inside of {:?}", tiles.len(), boundary);
+
+        let number_of_tiles = tiles.len();
+        let mut remaining = boundary;
+        for (index, tile) in tiles.iter_mut().enumerate() {
+            // The last tile takes all of the remaining space.
+            let bbox = if index + 1 == number_of_tiles {
+                remaining
+            } else {
+                let (bbox, rest) = self.split(remaining, self.side(index));
+                remaining = rest;
+                bbox
+            };
+            tile.bbox = gaps.apply_inner(boundary, bbox);
+        }
+    }
+}
+
+#[typetag::serde]
+impl Layout for DwindleLayout {
+    fn metadata(&self) -> LayoutMeta {
+        LayoutMeta {
+            name: "Dwindle Layout".into(),
+        }
+    }
+
+    fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    #[tracing::instrument(skip(self, update_info))]
+    fn layout<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
+        if self.is_dirty() {
+            if update_info.tiles.is_empty() {
+                trace!("No tiles inside of workspace. Early return");
+                return;
+            }
+            self.layout0(update_info);
+            self.dirty = false;
+        }
+    }
+
+    fn handle_message(&mut self, message: &LayoutMessage, _info: &LayoutMessageInfo) -> bool {
+        match *message {
+            LayoutMessage::GrowMaster(delta) => {
+                self.split_ratio = clamp_ratio(self.split_ratio + delta)
+            }
+            LayoutMessage::ShrinkMaster(delta) => {
+                self.split_ratio = clamp_ratio(self.split_ratio - delta)
+            }
+            _ => return false,
+        }
+
+        self.invalidate();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::tests::{bboxes, workspace_with_tiles};
+    use pretty_assertions::assert_eq;
+
+    fn layout_tiles(layout: DwindleLayout, number_of_tiles: usize) -> Vec<BBox> {
+        let bbox = BBox::with(0, 0, 1600, 800);
+        bboxes(&workspace_with_tiles(
+            Box::new(layout),
+            bbox,
+            number_of_tiles,
+        ))
+    }
+
+    #[test]
+    fn test_dwindle() {
+        let expected = vec![
+            BBox::with(0, 0, 800, 800),
+            BBox::with(800, 0, 800, 400),
+            BBox::with(800, 400, 400, 400),
+            BBox::with(1200, 400, 400, 400),
+        ];
+
+        assert_eq!(expected, layout_tiles(DwindleLayout::new(), 4));
+    }
+
+    #[test]
+    fn test_spiral() {
+        let layout = DwindleLayout::with(0.5, Direction::Left, true);
+        let expected = vec![
+            BBox::with(0, 0, 800, 800),
+            BBox::with(800, 0, 800, 400),
+            BBox::with(1200, 400, 400, 400),
+            BBox::with(800, 600, 400, 200),
+            BBox::with(800, 400, 400, 200),
+        ];
+
+        assert_eq!(expected, layout_tiles(layout, 5));
+    }
+}
@@ -0,0 +1,113 @@
+//! Gaps add empty space between neighbouring tiles (`inner`) and between tiles
+//! and the edges of the workspace (`outer`).
+//!
+//! Layouts first shrink the workspace's bounding box by the outer gaps and lay
+//! out their tiles flush inside of it. Afterwards, each tile is shrunk on all
+//! edges that do not touch the boundary, resulting in exactly `inner` pixels
+//! between two neighbouring tiles.
+
+use crate::bbox::{BBox, Margins};
+use serde::{Deserialize, Serialize};
+
+/// The gaps that are applied by layouts.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Gaps {
+    /// The gap between two neighbouring tiles.
+    pub inner: i32,
+    /// The gap between tiles and each edge of the workspace.
+    pub outer: Margins,
+}
+
+impl Gaps {
+    /// Creates empty gaps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates gaps.
+    ///
+    /// # Arguments
+    ///
+    /// - `inner`: The gap between two neighbouring tiles.
+    /// - `outer`: The gap between tiles and each edge of the workspace.
+    pub fn with(inner: i32, outer: Margins) -> Self {
+        Self { inner, outer }
+    }
+
+    /// Returns the bounding box that tiles are laid out in.
+    ///
+    /// # Arguments
+    ///
+    /// - `workspace_bbox`: The bounding box of the workspace.
+    pub fn apply_outer(&self, workspace_bbox: BBox) -> BBox {
+        workspace_bbox.inset(self.outer)
+    }
+
+    /// Shrinks a tile's bounding box by the inner gap.
+    ///
+    /// Only edges that do not touch the boundary are shrunk. Two neighbouring
+    /// tiles share the inner gap: the leading (left and top) edges take the
+    /// bigger half, the trailing (right and bottom) edges the smaller one.
+    ///
+    /// # Arguments
+    ///
+    /// - `boundary`: The bounding box all tiles were laid out in.
+    /// - `bbox`: The bounding box of the tile.
+    pub fn apply_inner(&self, boundary: BBox, bbox: BBox) -> BBox {
+        let trailing = self.inner / 2;
+        let leading = self.inner - trailing;
+
+        let margins = Margins::with(
+            if bbox.top() > boundary.top() {
+                leading
+            } else {
+                0
+            },
+            if bbox.right() < boundary.right() {
+                trailing
+            } else {
+                0
+            },
+            if bbox.bottom() < boundary.bottom() {
+                trailing
+            } else {
+                0
+            },
+            if bbox.left() > boundary.left() {
+                leading
+            } else {
+                0
+            },
+        );
+        bbox.inset(margins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_apply_outer() {
+        let gaps = Gaps::with(0, Margins::with(30, 10, 10, 10));
+        let bbox = gaps.apply_outer(BBox::with(0, 0, 1920, 1080));
+
+        assert_eq!(BBox::with(10, 30, 1900, 1040), bbox);
+    }
+
+    #[test]
+    fn test_apply_inner() {
+        let gaps = Gaps::with(5, Margins::new());
+        let boundary = BBox::with(0, 0, 1920, 1080);
+        let splitted = boundary.vertical_split();
+
+        let left = gaps.apply_inner(boundary, splitted.left());
+        let right = gaps.apply_inner(boundary, splitted.right());
+
+        assert_eq!(BBox::with(0, 0, 958, 1080), left);
+        assert_eq!(BBox::with(963, 0, 957, 1080), right);
+        assert_eq!(5, right.x - (left.x + left.width));
+    }
+}
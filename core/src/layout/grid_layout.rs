@@ -0,0 +1,181 @@
+//! A grid layout arranges tiles in rows and columns of equal size, keeping the
+//! grid as close to a square as possible.
+//!
+//! If the number of tiles does not fill the grid, the tiles of the last row
+//! share its whole width.
+
+use crate::{
+    bbox::{BBox, SplitDirection},
+    layout::{Layout, LayoutMeta, LayoutUpdateInfo},
+};
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+/// A grid layout implementation.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(default)]
+pub struct GridLayout {
+    /// Whether the layout is dirty or not.
+    dirty: bool,
+    /// Whether the aspect ratio of the workspace is taken into account. If
+    /// `true`, wide workspaces get more columns than rows and vice versa.
+    pub respect_aspect_ratio: bool,
+}
+
+impl GridLayout {
+    /// Creates a default `GridLayout`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `GridLayout`.
+    ///
+    /// # Arguments
+    ///
+    /// - `respect_aspect_ratio`: Whether the aspect ratio of the workspace is
+    ///   taken into account.
+    pub fn with(respect_aspect_ratio: bool) -> Self {
+        Self {
+            respect_aspect_ratio,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the number of columns and rows for a given number of tiles.
+    ///
+    /// # Arguments
+    ///
+    /// - `number_of_tiles`: The number of tiles. Must be greater than zero.
+    /// - `boundary`: The bounding box the grid is laid out in.
+    fn dimensions(self, number_of_tiles: usize, boundary: BBox) -> (usize, usize) {
+        let aspect_ratio = if self.respect_aspect_ratio && boundary.height > 0 {
+            f64::from(boundary.width) / f64::from(boundary.height)
+        } else {
+            1.0
+        };
+
+        let columns = (number_of_tiles as f64 * aspect_ratio).sqrt().ceil() as usize;
+        let columns = columns.max(1).min(number_of_tiles);
+        let rows = ceil_div(number_of_tiles, columns);
+
+        // Shrink the number of columns if the last column would stay empty.
+        (ceil_div(number_of_tiles, rows), rows)
+    }
+
+    // The logic behind the grid layout.
+    #[tracing::instrument(skip(self, update_info))]
+    fn layout0<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
+        let gaps = update_info.gaps;
+        let boundary = gaps.apply_outer(update_info.workspace_bbox);
+        let tiles = &mut update_info.tiles;
+
+        let (columns, rows) = self.dimensions(tiles.len(), boundary);
+        trace!(
+            "Aligning {} tiles in {} columns and {} rows inside of {:?}",
+            tiles.len(),
+            columns,
+            rows,
+            boundary
+        );
+
+        let row_bboxes =
+            BBox::weighted_split(boundary, &vec![1.0; rows], SplitDirection::Horizontal);
+        let mut tiles = tiles.iter_mut();
+        for (row, row_bbox) in row_bboxes.into_iter().enumerate() {
+            let number_of_columns = if row + 1 == rows {
+                tiles.len()
+            } else {
+                columns
+            };
+
+            let bboxes = BBox::weighted_split(
+                row_bbox,
+                &vec![1.0; number_of_columns],
+                SplitDirection::Vertical,
+            );
+            // The bounding boxes are polled first, so no tile is skipped once the
+            // row is full.
+            for (bbox, tile) in bboxes.into_iter().zip(&mut tiles) {
+                tile.bbox = gaps.apply_inner(boundary, bbox);
+            }
+        }
+    }
+}
+
+// Divides two numbers, rounding up.
+fn ceil_div(dividend: usize, divisor: usize) -> usize {
+    (dividend + divisor - 1) / divisor
+}
+
+#[typetag::serde]
+impl Layout for GridLayout {
+    fn metadata(&self) -> LayoutMeta {
+        LayoutMeta {
+            name: "Grid Layout".into(),
+        }
+    }
+
+    fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    #[tracing::instrument(skip(self, update_info))]
+    fn layout<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
+        if self.is_dirty() {
+            if update_info.tiles.is_empty() {
+                trace!("No tiles inside of workspace. Early return");
+                return;
+            }
+            self.layout0(update_info);
+            self.dirty = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::tests::{bboxes, workspace_with_tiles};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_layout() {
+        let bbox = BBox::with(0, 0, 1200, 800);
+        let workspace = workspace_with_tiles(Box::new(GridLayout::new()), bbox, 5);
+
+        let expected = vec![
+            BBox::with(0, 0, 400, 400),
+            BBox::with(400, 0, 400, 400),
+            BBox::with(800, 0, 400, 400),
+            BBox::with(0, 400, 600, 400),
+            BBox::with(600, 400, 600, 400),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_layout_respects_aspect_ratio() {
+        let bbox = BBox::with(0, 0, 3000, 1000);
+        let workspace = workspace_with_tiles(Box::new(GridLayout::with(true)), bbox, 3);
+
+        let expected = vec![
+            BBox::with(0, 0, 1000, 1000),
+            BBox::with(1000, 0, 1000, 1000),
+            BBox::with(2000, 0, 1000, 1000),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let layout: Box<dyn Layout> = Box::new(GridLayout::with(true));
+        let json = serde_json::to_string(&layout).unwrap();
+        let layout: Box<dyn Layout> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!("Grid Layout", layout.metadata().name);
+    }
+}
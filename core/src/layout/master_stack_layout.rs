@@ -0,0 +1,343 @@
+//! A master/stack layout puts a configurable number of master tiles into the
+//! master area on one side of the workspace. The rest of the tiles share the
+//! remaining stack area.
+//!
+//! The master tiles are the first tiles in the workspace's order.
+//!
+//! Both the share of the master area and the share of each tile inside of its
+//! area can be changed by resizing tiles.
+
+use crate::{
+    bbox::{BBox, SplitDirection},
+    layout::{
+        clamp_ratio, split_side, Layout, LayoutMessage, LayoutMessageInfo, LayoutMeta,
+        LayoutUpdateInfo, ResizeAmount, MIN_RATIO,
+    },
+    tile::TileId,
+    util::Direction,
+};
+use serde::{Deserialize, Serialize};
+use std::{cmp::min, collections::HashMap};
+use tracing::trace;
+
+/// A master/stack layout implementation.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct MasterStackLayout {
+    /// Whether the layout is dirty or not.
+    dirty: bool,
+    /// The side that the master area is rendered to.
+    pub side: Direction,
+    /// The number of master tiles.
+    pub master_count: usize,
+    /// The share of the workspace that the master area takes.
+    pub master_ratio: f64,
+    /// The weights of the tiles inside of their area. Tiles without a weight
+    /// have a weight of `1.0`.
+    weights: HashMap<TileId, f64>,
+}
+
+impl Default for MasterStackLayout {
+    /// Returns a default instance with one master tile on the left side that
+    /// takes half of the workspace.
+    fn default() -> Self {
+        Self {
+            dirty: false,
+            side: Direction::Left,
+            master_count: 1,
+            master_ratio: 0.5,
+            weights: HashMap::new(),
+        }
+    }
+}
+
+impl MasterStackLayout {
+    /// Creates a default `MasterStackLayout`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `MasterStackLayout`.
+    ///
+    /// # Arguments
+    ///
+    /// - `side`: The side that the master area is rendered to.
+    /// - `master_count`: The number of master tiles.
+    /// - `master_ratio`: The share of the workspace that the master area takes.
+    ///   The value is clamped to `0.05..=0.95`.
+    pub fn with(side: Direction, master_count: usize, master_ratio: f64) -> Self {
+        Self {
+            side,
+            master_count,
+            master_ratio: clamp_ratio(master_ratio),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the master and the stack area.
+    fn split(&self, boundary: BBox) -> (BBox, BBox) {
+        split_side(boundary, self.side, self.master_ratio)
+    }
+
+    /// Returns the weight of a tile inside of its area.
+    fn weight(&self, tile_id: TileId) -> f64 {
+        self.weights.get(&tile_id).cloned().unwrap_or(1.0)
+    }
+
+    /// Returns the split direction used for splitting up the master and the
+    /// stack area.
+    fn split_direction(&self) -> SplitDirection {
+        match self.side {
+            Direction::Left | Direction::Right => SplitDirection::Horizontal,
+            Direction::Up | Direction::Down => SplitDirection::Vertical,
+        }
+    }
+
+    // The logic behind the master/stack layout.
+    #[tracing::instrument(skip(self, update_info))]
+    fn layout0<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
+        let gaps = update_info.gaps;
+        let boundary = gaps.apply_outer(update_info.workspace_bbox);
+        let tiles = &mut update_info.tiles;
+
+        let number_of_masters = min(self.master_count, tiles.len());
+        let number_of_stacked = tiles.len() - number_of_masters;
+        trace!(
+            "Aligning {} master and {} stacked tiles inside of {:?}",
+            number_of_masters,
+            number_of_stacked,
+            boundary
+        );
+
+        // Forget the weights of tiles that are gone.
+        self.weights
+            .retain(|tile_id, _| tiles.iter().any(|tile| tile.id == *tile_id));
+
+        // If one of the areas is empty, the other one takes the whole space.
+        let (master_bbox, stack_bbox) = if number_of_masters == 0 || number_of_stacked == 0 {
+            (boundary, boundary)
+        } else {
+            self.split(boundary)
+        };
+
+        let split_direction = self.split_direction();
+        let weights: Vec<f64> = tiles.iter().map(|tile| self.weight(tile.id)).collect();
+        let (master_weights, stack_weights) = weights.split_at(number_of_masters);
+        let bboxes = BBox::weighted_split(master_bbox, master_weights, split_direction)
+            .into_iter()
+            .chain(BBox::weighted_split(
+                stack_bbox,
+                stack_weights,
+                split_direction,
+            ));
+        for (tile, bbox) in tiles.iter_mut().zip(bboxes) {
+            tile.bbox = gaps.apply_inner(boundary, bbox);
+        }
+    }
+}
+
+impl MasterStackLayout {
+    // Moves the edge of the focused tile that faces the given direction.
+    fn resize(
+        &mut self,
+        direction: Direction,
+        amount: ResizeAmount,
+        info: &LayoutMessageInfo,
+    ) -> bool {
+        let focused_index = match info.focused_index() {
+            Some(index) => index,
+            None => return false,
+        };
+        let number_of_masters = min(self.master_count, info.tiles.len());
+        let delta = info.resize_ratio(direction, amount);
+
+        if focused_index < number_of_masters
+            && number_of_masters < info.tiles.len()
+            && direction == self.side.opposite()
+        {
+            self.master_ratio = clamp_ratio(self.master_ratio + delta);
+            true
+        } else if focused_index >= number_of_masters
+            && number_of_masters > 0
+            && direction == self.side
+        {
+            self.master_ratio = clamp_ratio(self.master_ratio - delta);
+            true
+        } else if direction == self.side || direction == self.side.opposite() {
+            false
+        } else {
+            // The tile is resized inside of its area by moving weight from its
+            // neighbour to it.
+            let area = if focused_index < number_of_masters {
+                &info.tiles[..number_of_masters]
+            } else {
+                &info.tiles[number_of_masters..]
+            };
+            let area: Vec<TileId> = area.iter().map(|tile| tile.id).collect();
+            let position = if focused_index < number_of_masters {
+                focused_index
+            } else {
+                focused_index - number_of_masters
+            };
+            let neighbour = match direction {
+                Direction::Left | Direction::Up if position > 0 => area[position - 1],
+                Direction::Right | Direction::Down if position + 1 < area.len() => {
+                    area[position + 1]
+                }
+                _ => return false,
+            };
+
+            let focused_tile_id = area[position];
+            let total: f64 = area.iter().map(|&id| self.weight(id)).sum();
+            let min_weight = MIN_RATIO * total;
+            let (weight, neighbour_weight) = (self.weight(focused_tile_id), self.weight(neighbour));
+            let delta = (delta * total)
+                .min(neighbour_weight - min_weight)
+                .max(min_weight - weight);
+            self.weights.insert(focused_tile_id, weight + delta);
+            self.weights.insert(neighbour, neighbour_weight - delta);
+            true
+        }
+    }
+}
+
+#[typetag::serde]
+impl Layout for MasterStackLayout {
+    fn metadata(&self) -> LayoutMeta {
+        LayoutMeta {
+            name: "Master Stack Layout".into(),
+        }
+    }
+
+    fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    #[tracing::instrument(skip(self, update_info))]
+    fn layout<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
+        if self.is_dirty() {
+            if update_info.tiles.is_empty() {
+                trace!("No tiles inside of workspace. Early return");
+                return;
+            }
+            self.layout0(update_info);
+            self.dirty = false;
+        }
+    }
+
+    fn handle_message(&mut self, message: &LayoutMessage, info: &LayoutMessageInfo) -> bool {
+        match *message {
+            LayoutMessage::IncrementMasterCount => self.master_count += 1,
+            LayoutMessage::DecrementMasterCount => {
+                if self.master_count == 0 {
+                    return false;
+                }
+                self.master_count -= 1;
+            }
+            LayoutMessage::GrowMaster(delta) => {
+                self.master_ratio = clamp_ratio(self.master_ratio + delta)
+            }
+            LayoutMessage::ShrinkMaster(delta) => {
+                self.master_ratio = clamp_ratio(self.master_ratio - delta)
+            }
+            LayoutMessage::Resize(direction, amount) => {
+                if !self.resize(direction, amount, info) {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+
+        self.invalidate();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        layout::tests::{bboxes, workspace_with_tiles},
+        workspace::Workspace,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn tiled_workspace(layout: MasterStackLayout, number_of_tiles: usize) -> Workspace {
+        let bbox = BBox::with(0, 0, 1000, 900);
+        workspace_with_tiles(Box::new(layout), bbox, number_of_tiles)
+    }
+
+    #[test]
+    fn test_layout() {
+        let workspace = tiled_workspace(MasterStackLayout::new(), 3);
+
+        let expected = vec![
+            BBox::with(0, 0, 500, 900),
+            BBox::with(500, 0, 500, 450),
+            BBox::with(500, 450, 500, 450),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_layout_messages() {
+        let layout = MasterStackLayout::with(Direction::Up, 1, 0.6);
+        let mut workspace = tiled_workspace(layout, 3);
+
+        assert!(workspace.send_layout_message(&LayoutMessage::IncrementMasterCount));
+        assert!(workspace.send_layout_message(&LayoutMessage::ShrinkMaster(0.1)));
+        workspace.layout();
+
+        let expected = vec![
+            BBox::with(0, 0, 500, 450),
+            BBox::with(500, 0, 500, 450),
+            BBox::with(0, 450, 1000, 450),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_all_tiles_are_masters() {
+        let layout = MasterStackLayout::with(Direction::Right, 5, 0.5);
+        let workspace = tiled_workspace(layout, 2);
+
+        let expected = vec![BBox::with(0, 0, 1000, 450), BBox::with(0, 450, 1000, 450)];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut workspace = tiled_workspace(MasterStackLayout::new(), 2);
+
+        workspace.focus_tile(0);
+        assert!(workspace.resize_focused(Direction::Right, ResizeAmount::Pixels(100)));
+        assert!(!workspace.resize_focused(Direction::Up, ResizeAmount::Pixels(100)));
+        workspace.focus_tile(1);
+        assert!(workspace.resize_focused(Direction::Left, ResizeAmount::Percent(30.0)));
+        workspace.layout();
+
+        let expected = vec![BBox::with(0, 0, 300, 900), BBox::with(300, 0, 700, 900)];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_resize_inside_of_area() {
+        let mut workspace = tiled_workspace(MasterStackLayout::new(), 3);
+
+        workspace.focus_tile(1);
+        assert!(workspace.resize_focused(Direction::Down, ResizeAmount::Pixels(150)));
+        assert!(!workspace.resize_focused(Direction::Up, ResizeAmount::Pixels(-150)));
+        workspace.layout();
+
+        let expected = vec![
+            BBox::with(0, 0, 500, 900),
+            BBox::with(500, 0, 500, 600),
+            BBox::with(500, 600, 500, 300),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+}
@@ -1,16 +1,164 @@
+//! A middle layout puts a `main` tile into a centered column. The rest of the
+//! tiles are alternately distributed into a left and a right stack, starting
+//! with the left one.
 //!
+//! The center column always stays centered, so the right column is left empty
+//! if there are not enough tiles for it. A single tile takes the whole space.
 
-use crate::layout::{Layout, LayoutMeta, LayoutUpdateInfo};
+use crate::{
+    bbox::{BBox, SplitDirection},
+    layout::{
+        clamp_ratio, Layout, LayoutMessage, LayoutMessageInfo, LayoutMeta, LayoutUpdateInfo,
+        MainTile, ResizeAmount,
+    },
+    util::Direction,
+};
 use serde::{Deserialize, Serialize};
+use tracing::trace;
 
-///
-#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+/// A middle layout implementation.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
 pub struct MiddleLayout {
+    /// Whether the layout is dirty or not.
     dirty: bool,
+    /// The share of the workspace's width that the center column takes.
+    pub center_ratio: f64,
+    /// The tile that is rendered to the center column.
+    pub main: MainTile,
+}
+
+impl Default for MiddleLayout {
+    /// Returns a default instance with a center column that takes half of the
+    /// workspace's width.
+    fn default() -> Self {
+        Self {
+            dirty: false,
+            center_ratio: 0.5,
+            main: MainTile::default(),
+        }
+    }
+}
+
+impl MiddleLayout {
+    /// Creates a default `MiddleLayout`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `MiddleLayout`.
+    ///
+    /// # Arguments
+    ///
+    /// - `center_ratio`: The share of the workspace's width that the center
+    ///   column takes. The value is clamped to `0.05..=0.95`.
+    pub fn with(center_ratio: f64) -> Self {
+        Self {
+            center_ratio: clamp_ratio(center_ratio),
+            ..Self::default()
+        }
+    }
+
+    // The logic behind the middle layout.
+    #[tracing::instrument(skip(self, update_info))]
+    fn layout0<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
+        let gaps = update_info.gaps;
+        let boundary = gaps.apply_outer(update_info.workspace_bbox);
+        let main_index = match update_info.main_tile_index(self.main) {
+            Some(index) => index,
+            None => return,
+        };
+        let tiles = &mut update_info.tiles;
+
+        let number_of_left = tiles.len() / 2;
+        let number_of_right = (tiles.len() - 1) / 2;
+        trace!(
+            "Aligning {} left and {} right tiles inside of {:?}",
+            number_of_left,
+            number_of_right,
+            boundary
+        );
+
+        let center_ratio = clamp_ratio(self.center_ratio);
+        let side_ratio = (1.0 - center_ratio) / 2.0;
+        let columns = BBox::weighted_split(
+            boundary,
+            &[side_ratio, center_ratio, side_ratio],
+            SplitDirection::Vertical,
+        );
+        let (left_bbox, center_bbox, right_bbox) = (columns[0], columns[1], columns[2]);
+
+        // A single tile takes the whole space.
+        let center_bbox = if number_of_left == 0 {
+            boundary
+        } else {
+            center_bbox
+        };
+
+        let mut left_bboxes = BBox::weighted_split(
+            left_bbox,
+            &vec![1.0; number_of_left],
+            SplitDirection::Horizontal,
+        )
+        .into_iter();
+        let mut right_bboxes = BBox::weighted_split(
+            right_bbox,
+            &vec![1.0; number_of_right],
+            SplitDirection::Horizontal,
+        )
+        .into_iter();
+
+        let mut stacked = 0;
+        for (index, tile) in tiles.iter_mut().enumerate() {
+            let bbox = if index == main_index {
+                Some(center_bbox)
+            } else {
+                stacked += 1;
+                if stacked % 2 == 1 {
+                    left_bboxes.next()
+                } else {
+                    right_bboxes.next()
+                }
+            };
+
+            if let Some(bbox) = bbox {
+                tile.bbox = gaps.apply_inner(boundary, bbox);
+            }
+        }
+    }
 }
 
 impl MiddleLayout {
-    fn layout0<'a>(&mut self, _update_info: &'a mut LayoutUpdateInfo<'a>) {}
+    // Moves the edge of the focused tile that faces the given direction. The
+    // center column stays centered, so both of its edges move.
+    fn resize(
+        &mut self,
+        direction: Direction,
+        amount: ResizeAmount,
+        info: &LayoutMessageInfo,
+    ) -> bool {
+        let (focused_index, main_index) =
+            match (info.focused_index(), info.main_tile_index(self.main)) {
+                (Some(focused_index), Some(main_index)) => (focused_index, main_index),
+                _ => return false,
+            };
+        let delta = 2.0 * info.resize_ratio(direction, amount);
+
+        // The rest tiles alternate between the left and the right stack.
+        let stacked = if focused_index > main_index {
+            focused_index
+        } else {
+            focused_index + 1
+        };
+        let growing = match direction {
+            Direction::Left | Direction::Right if focused_index == main_index => delta,
+            Direction::Right if stacked % 2 == 1 => -delta,
+            Direction::Left if stacked % 2 == 0 => -delta,
+            _ => return false,
+        };
+        self.center_ratio = clamp_ratio(self.center_ratio + growing);
+        true
+    }
 }
 
 #[typetag::serde]
@@ -29,7 +177,98 @@ impl Layout for MiddleLayout {
         self.dirty
     }
 
+    #[tracing::instrument(skip(self, update_info))]
     fn layout<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
-        self.layout0(update_info)
+        if self.is_dirty() {
+            if update_info.tiles.is_empty() {
+                trace!("No tiles inside of workspace. Early return");
+                return;
+            }
+            self.layout0(update_info);
+            self.dirty = false;
+        }
+    }
+
+    fn handle_message(&mut self, message: &LayoutMessage, info: &LayoutMessageInfo) -> bool {
+        match *message {
+            LayoutMessage::GrowMaster(delta) => {
+                self.center_ratio = clamp_ratio(self.center_ratio + delta)
+            }
+            LayoutMessage::ShrinkMaster(delta) => {
+                self.center_ratio = clamp_ratio(self.center_ratio - delta)
+            }
+            LayoutMessage::Resize(direction, amount) => {
+                if !self.resize(direction, amount, info) {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+
+        self.invalidate();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        layout::tests::{bboxes, workspace_with_tiles},
+        workspace::Workspace,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn tiled_workspace(number_of_tiles: usize) -> Workspace {
+        let bbox = BBox::with(0, 0, 2000, 1000);
+        workspace_with_tiles(Box::new(MiddleLayout::new()), bbox, number_of_tiles)
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut workspace = tiled_workspace(4);
+        workspace.focus_tile(0);
+        workspace.layout();
+
+        let expected = vec![
+            BBox::with(500, 0, 1000, 1000),
+            BBox::with(0, 0, 500, 500),
+            BBox::with(1500, 0, 500, 1000),
+            BBox::with(0, 500, 500, 500),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_layout_without_right_stack() {
+        let workspace = tiled_workspace(2);
+
+        let expected = vec![BBox::with(0, 0, 500, 1000), BBox::with(500, 0, 1000, 1000)];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_layout_single_tile() {
+        let workspace = tiled_workspace(1);
+
+        assert_eq!(vec![BBox::with(0, 0, 2000, 1000)], bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_layout_follows_focus() {
+        let mut workspace = tiled_workspace(4);
+        workspace.focus_tile(0);
+        workspace.layout();
+
+        workspace.focus_tile(2);
+        workspace.layout();
+
+        let expected = vec![
+            BBox::with(0, 0, 500, 500),
+            BBox::with(1500, 0, 500, 1000),
+            BBox::with(500, 0, 1000, 1000),
+            BBox::with(0, 500, 500, 500),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
     }
 }
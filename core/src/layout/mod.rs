@@ -1,14 +1,26 @@
 //! A layout is responsible for laying out tiles inside a workspace.
 //!
 //! These is done by changing the bounding box of each tile according to the
-//! layout's implementation details.
+//! layout's implementation details. Layouts can additionally hide tiles, e.g.
+//! if they are covered by other tiles.
 
-use crate::{bbox::BBox, tile::Tile};
+use crate::{
+    bbox::{BBox, SplitDirection},
+    tile::{Tile, TileId},
+    util::Direction,
+};
 pub use dyn_clone::DynClone;
+use gaps::Gaps;
 use serde::{Deserialize, Serialize};
 use std::{fmt::Debug, hash::Hash};
 
+pub mod bsp_layout;
+pub mod dwindle_layout;
+pub mod gaps;
+pub mod grid_layout;
+pub mod master_stack_layout;
 pub mod middle_layout;
+pub mod monocle_layout;
 pub mod sided_layout;
 
 /// Metadata associated with a layout.
@@ -18,14 +30,232 @@ pub struct LayoutMeta {
     pub name: String,
 }
 
+/// The tile that a layout puts into its main area.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum MainTile {
+    /// The focused tile. If no tile is focused, the master tile is used.
+    Focused,
+    /// The master tile, which is the first tile in the workspace's order.
+    Master,
+}
+
+impl Default for MainTile {
+    /// Returns the default main tile (Focused).
+    fn default() -> Self {
+        MainTile::Focused
+    }
+}
+
+/// A layout-specific command, e.g. triggered by a key binding.
+///
+/// Layouts ignore messages that they do not support.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum LayoutMessage {
+    /// Increments the number of master tiles.
+    IncrementMasterCount,
+    /// Decrements the number of master tiles.
+    DecrementMasterCount,
+    /// Grows the master area by the given share of the workspace.
+    GrowMaster(f64),
+    /// Shrinks the master area by the given share of the workspace.
+    ShrinkMaster(f64),
+    /// Sets the direction used to split the focused tile when a new tile is
+    /// added. If `None`, the direction is chosen automatically.
+    SetSplitDirection(Option<SplitDirection>),
+    /// Rotates the split containing the focused tile clockwise by 90 degrees.
+    Rotate,
+    /// Mirrors the split containing the focused tile along the given
+    /// direction.
+    Flip(SplitDirection),
+    /// Grows the focused tile inside of its split by the given share of the
+    /// split. Negative values shrink the tile.
+    ResizeSplit(f64),
+    /// Moves the edge of the focused tile that faces the given direction by
+    /// the given amount. Positive amounts grow the tile, negative amounts
+    /// shrink it.
+    Resize(Direction, ResizeAmount),
+}
+
+/// The amount by which a tile is resized.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ResizeAmount {
+    /// An absolute number of pixels in logical units, so it scales with the
+    /// display.
+    Pixels(i32),
+    /// A percentage of the workspace's extent along the resized axis.
+    Percent(f64),
+}
+
+impl ResizeAmount {
+    /// Returns the amount in pixels.
+    ///
+    /// # Arguments
+    ///
+    /// - `length`: The workspace's extent along the resized axis.
+    pub fn to_pixels(self, length: i32) -> f64 {
+        match self {
+            ResizeAmount::Pixels(pixels) => f64::from(pixels),
+            ResizeAmount::Percent(percent) => percent / 100.0 * f64::from(length),
+        }
+    }
+}
+
+/// The information passed to a layout together with a message.
+#[derive(Debug, Eq, Hash, PartialEq, Serialize)]
+pub struct LayoutMessageInfo<'a> {
+    /// All tiles managed by the layout, in the order of the workspace.
+    pub tiles: Vec<&'a Tile>,
+    /// The bounding box of the workspace in logical units.
+    pub workspace_bbox: BBox,
+    /// The gaps between tiles and between tiles and the workspace's edges.
+    pub gaps: Gaps,
+    /// The id of the focused tile. If `None`, no tile is focused.
+    pub focused_tile_id: Option<TileId>,
+}
+
+impl<'a> LayoutMessageInfo<'a> {
+    /// Returns the bounding box that tiles are laid out in, i.e. the
+    /// workspace's bounding box without the outer gaps.
+    pub fn boundary(&self) -> BBox {
+        self.gaps.apply_outer(self.workspace_bbox)
+    }
+
+    /// Returns the index of the focused tile.
+    ///
+    /// # Returns
+    ///
+    /// `Some(usize)` if a tile managed by the layout is focused, `None`
+    /// otherwise.
+    pub fn focused_index(&self) -> Option<usize> {
+        self.tiles
+            .iter()
+            .position(|tile| Some(tile.id) == self.focused_tile_id)
+    }
+
+    /// Returns the index of the tile that is put into the main area.
+    ///
+    /// # Arguments
+    ///
+    /// - `main_tile`: Which tile takes the main area.
+    ///
+    /// # Returns
+    ///
+    /// `Some(usize)` if the workspace has tiles, `None` otherwise.
+    pub fn main_tile_index(&self, main_tile: MainTile) -> Option<usize> {
+        main_tile_index(self.tiles.len(), self.focused_index(), main_tile)
+    }
+
+    /// Returns the amount of a resize as a share of the boundary's extent
+    /// along the axis of the given direction.
+    ///
+    /// # Arguments
+    ///
+    /// - `direction`: The direction of the resize.
+    /// - `amount`: The amount of the resize.
+    pub fn resize_ratio(&self, direction: Direction, amount: ResizeAmount) -> f64 {
+        let boundary = self.boundary();
+        let length = match direction {
+            Direction::Left | Direction::Right => boundary.width,
+            Direction::Up | Direction::Down => boundary.height,
+        };
+        if length <= 0 {
+            return 0.0;
+        }
+        amount.to_pixels(length) / f64::from(length)
+    }
+}
+
 /// The information needed to update tiles inside a workspace.
 #[derive(Debug, Eq, Hash, PartialEq, Serialize)]
 pub struct LayoutUpdateInfo<'a> {
-    /// A mutable list of all tiles.
-    pub tiles: &'a mut Vec<Tile>,
-    /// The bounding box of the workspace. Tiles should only be layed out inside
-    /// these boundaries!
+    /// A mutable list of all tiles managed by the layout, in the order of the
+    /// workspace. Floating tiles are not part of it. The first tile is the
+    /// master tile. Layouts should keep this order when distributing tiles.
+    pub tiles: Vec<&'a mut Tile>,
+    /// The bounding box of the workspace in logical units. Tiles should only be
+    /// layed out inside these boundaries!
     pub workspace_bbox: BBox,
+    /// The gaps between tiles and between tiles and the workspace's edges.
+    pub gaps: Gaps,
+    /// The id of the focused tile. If `None`, no tile is focused.
+    pub focused_tile_id: Option<TileId>,
+    /// The ids of the tiles that got focused, from the most recently to the
+    /// least recently focused one. Tiles that were just added are focused
+    /// already, so this is used to find the tile that was focused before.
+    pub focus_history: &'a [TileId],
+}
+
+impl<'a> LayoutUpdateInfo<'a> {
+    /// Returns the index of the tile that should be put into the main area.
+    ///
+    /// # Arguments
+    ///
+    /// - `main_tile`: Which tile takes the main area.
+    ///
+    /// # Returns
+    ///
+    /// `Some(usize)` if the workspace has tiles, `None` otherwise.
+    pub fn main_tile_index(&self, main_tile: MainTile) -> Option<usize> {
+        let focused_index = self
+            .tiles
+            .iter()
+            .position(|tile| Some(tile.id) == self.focused_tile_id);
+        main_tile_index(self.tiles.len(), focused_index, main_tile)
+    }
+}
+
+// Returns the index of the tile that is put into the main area.
+fn main_tile_index(
+    number_of_tiles: usize,
+    focused_index: Option<usize>,
+    main_tile: MainTile,
+) -> Option<usize> {
+    if number_of_tiles == 0 {
+        return None;
+    }
+
+    match main_tile {
+        MainTile::Focused => Some(focused_index.unwrap_or(0)),
+        MainTile::Master => Some(0),
+    }
+}
+
+/// The smallest share a split gives to one of its parts.
+pub(crate) const MIN_RATIO: f64 = 0.05;
+/// The biggest share a split gives to one of its parts.
+pub(crate) const MAX_RATIO: f64 = 0.95;
+
+// Clamps the ratio of a split to `MIN_RATIO..=MAX_RATIO`. `NaN` results in an
+// even split.
+pub(crate) fn clamp_ratio(ratio: f64) -> f64 {
+    if ratio.is_nan() {
+        return 0.5;
+    }
+    ratio.max(MIN_RATIO).min(MAX_RATIO)
+}
+
+// Splits a bounding box into the part at the given side, which receives the
+// clamped ratio of the space, and the rest.
+pub(crate) fn split_side(boundary: BBox, side: Direction, ratio: f64) -> (BBox, BBox) {
+    let ratio = clamp_ratio(ratio);
+    match side {
+        Direction::Left => {
+            let splitted = boundary.vertical_split_by(ratio);
+            (splitted.left(), splitted.right())
+        }
+        Direction::Right => {
+            let splitted = boundary.vertical_split_by(1.0 - ratio);
+            (splitted.right(), splitted.left())
+        }
+        Direction::Up => {
+            let splitted = boundary.horizontal_split_by(ratio);
+            (splitted.upper(), splitted.lower())
+        }
+        Direction::Down => {
+            let splitted = boundary.horizontal_split_by(1.0 - ratio);
+            (splitted.lower(), splitted.upper())
+        }
+    }
 }
 
 /// A layout.
@@ -38,8 +268,51 @@ pub trait Layout: DynClone + Debug + Send + Sync {
     /// Returns whether the layout is dirty or not. Dirty layouts need
     /// re-calculations.
     fn is_dirty(&self) -> bool;
-    /// Lays out tiles by changing their bbox.
+    /// Lays out tiles by changing their bbox and visibility.
+    ///
+    /// Before a dirty layout is called, all tiles are marked as visible.
     fn layout<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>);
+    /// Handles a layout-specific message. Layouts that handle a message are
+    /// invalidated.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the message was handled, `false` otherwise. The default
+    /// implementation ignores all messages.
+    fn handle_message(&mut self, _message: &LayoutMessage, _info: &LayoutMessageInfo) -> bool {
+        false
+    }
 }
 
 dyn_clone::clone_trait_object!(Layout);
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{display::Display, window::Window, workspace::Workspace};
+
+    // Creates a workspace with the given layout on a display with the given
+    // bounding box. The tiles are added one after another and the workspace is
+    // laid out after each of them, like a window manager would do.
+    pub(crate) fn workspace_with_tiles(
+        layout: Box<dyn Layout>,
+        bbox: BBox,
+        number_of_tiles: usize,
+    ) -> Workspace {
+        let mut workspace = Workspace::with(0, Display::with(0, bbox));
+        workspace.layout = layout;
+        for i in 0..number_of_tiles {
+            workspace
+                .add_tile(Tile::with(i as TileId, BBox::new(), Window::new()))
+                .unwrap();
+            workspace.layout();
+        }
+
+        workspace
+    }
+
+    // Returns the bounding boxes of all tiles in the workspace's order.
+    pub(crate) fn bboxes(workspace: &Workspace) -> Vec<BBox> {
+        workspace.iter().map(|tile| tile.bbox).collect()
+    }
+}
@@ -0,0 +1,101 @@
+//! A monocle layout gives every tile the whole workspace. Only the focused
+//! tile is visible, all other tiles are hidden.
+
+use crate::layout::{Layout, LayoutMeta, LayoutUpdateInfo, MainTile};
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+/// A monocle layout implementation.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct MonocleLayout {
+    /// Whether the layout is dirty or not.
+    dirty: bool,
+}
+
+impl MonocleLayout {
+    /// Creates a default `MonocleLayout`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The logic behind the monocle layout.
+    #[tracing::instrument(skip(self, update_info))]
+    fn layout0<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
+        let gaps = update_info.gaps;
+        let boundary = gaps.apply_outer(update_info.workspace_bbox);
+        trace!(
+            "Aligning {} tiles inside of {:?}",
+            update_info.tiles.len(),
+            boundary
+        );
+
+        for tile in update_info.tiles.iter_mut() {
+            tile.bbox = gaps.apply_inner(boundary, boundary);
+        }
+    }
+
+    // Hides all tiles but the focused one.
+    fn update_visibility(&self, update_info: &mut LayoutUpdateInfo) {
+        let visible_index = update_info.main_tile_index(MainTile::Focused);
+        for (index, tile) in update_info.tiles.iter_mut().enumerate() {
+            tile.hidden = Some(index) != visible_index;
+        }
+    }
+}
+
+#[typetag::serde]
+impl Layout for MonocleLayout {
+    fn metadata(&self) -> LayoutMeta {
+        LayoutMeta {
+            name: "Monocle Layout".into(),
+        }
+    }
+
+    fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    #[tracing::instrument(skip(self, update_info))]
+    fn layout<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
+        // The focus can change without invalidating the layout, so the
+        // visibility is updated on every call.
+        self.update_visibility(update_info);
+
+        if self.is_dirty() {
+            if update_info.tiles.is_empty() {
+                trace!("No tiles inside of workspace. Early return");
+                return;
+            }
+            self.layout0(update_info);
+            self.dirty = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bbox::BBox, layout::tests::workspace_with_tiles};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_layout() {
+        let bbox = BBox::with(0, 0, 1920, 1080);
+        let mut workspace = workspace_with_tiles(Box::new(MonocleLayout::new()), bbox, 3);
+
+        assert!(workspace
+            .iter()
+            .all(|tile| tile.bbox == BBox::with(0, 0, 1920, 1080)));
+        let hidden: Vec<bool> = workspace.iter().map(|tile| tile.hidden).collect();
+        assert_eq!(vec![true, true, false], hidden);
+
+        workspace.focus_tile(0);
+        workspace.layout();
+        let hidden: Vec<bool> = workspace.iter().map(|tile| tile.hidden).collect();
+        assert_eq!(vec![false, true, true], hidden);
+    }
+}
@@ -1,17 +1,27 @@
-//! A sided layout uses have of the workspace's bounding box for a `focused`
-//! tile. The rest of the space is evenly shared between the rest of the tiles.
+//! A sided layout uses half of the workspace's bounding box for a `main` tile.
+//! The rest of the space is evenly shared between the rest of the tiles.
+//!
+//! The main tile is either the focused tile or the master tile, depending on
+//! the layout's configuration.
+//!
+//! Both the share of the main tile and the share of each remaining tile can be
+//! changed by resizing tiles.
 
 use crate::{
     bbox::{BBox, SplitDirection},
-    layout::{Layout, LayoutMeta, LayoutUpdateInfo},
-    tile::Tile,
+    layout::{
+        clamp_ratio, split_side, Layout, LayoutMessage, LayoutMessageInfo, LayoutMeta,
+        LayoutUpdateInfo, MainTile, ResizeAmount, MIN_RATIO,
+    },
+    tile::TileId,
     util::Direction,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tracing::{debug, trace};
 
 /// A `SidedBBox` splits a bounding box into two parts, the `sided` part, which
-/// is taken by the `focused` tile and the `rest` part, that is shared between
+/// is taken by the `main` tile and the `rest` part, that is shared between
 /// the rest of the tiles.
 #[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 struct SidedBBox(BBox, BBox);
@@ -29,12 +39,36 @@ impl SidedBBox {
 }
 
 /// A sided layout implementation.
-#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct SidedLayout {
     /// Whether the layout is dirty or not.
     dirty: bool,
-    /// The side that the focused tile is rendered to.
+    /// The side that the main tile is rendered to.
     pub side: Direction,
+    /// The tile that is rendered to the side.
+    #[serde(default)]
+    pub main: MainTile,
+    /// The share of the workspace that the main tile takes.
+    #[serde(default = "default_ratio")]
+    pub ratio: f64,
+    /// The weights of the remaining tiles. Tiles without a weight have a
+    /// weight of `1.0`.
+    #[serde(default)]
+    weights: HashMap<TileId, f64>,
+}
+
+impl Default for SidedLayout {
+    /// Returns a default instance with the main tile on the left side that
+    /// takes half of the workspace.
+    fn default() -> Self {
+        Self {
+            dirty: false,
+            side: Direction::default(),
+            main: MainTile::default(),
+            ratio: default_ratio(),
+            weights: HashMap::new(),
+        }
+    }
 }
 
 impl SidedLayout {
@@ -47,7 +81,7 @@ impl SidedLayout {
     ///
     /// # Arguments
     ///
-    /// - `side`: The side on which the focused tile is rendered to.
+    /// - `side`: The side on which the main tile is rendered to.
     pub fn with(side: Direction) -> Self {
         Self {
             side,
@@ -55,27 +89,16 @@ impl SidedLayout {
         }
     }
 
-    /// Returns the bounding boxes depending on which side the focused tile is
+    /// Returns the bounding boxes depending on which side the main tile is
     /// rendered to.
-    fn bbox_for_side(self, boundary: BBox) -> SidedBBox {
-        match self.side {
-            Direction::Left => {
-                let splitted = boundary.vertical_split();
-                SidedBBox(splitted.left(), splitted.right())
-            }
-            Direction::Right => {
-                let splitted = boundary.vertical_split();
-                SidedBBox(splitted.right(), splitted.left())
-            }
-            Direction::Up => {
-                let splitted = boundary.horizontal_split();
-                SidedBBox(splitted.upper(), splitted.lower())
-            }
-            Direction::Down => {
-                let splitted = boundary.horizontal_split();
-                SidedBBox(splitted.lower(), splitted.upper())
-            }
-        }
+    fn bbox_for_side(&self, boundary: BBox) -> SidedBBox {
+        let (sided, rest) = split_side(boundary, self.side, self.ratio);
+        SidedBBox(sided, rest)
+    }
+
+    /// Returns the weight of a remaining tile.
+    fn weight(&self, tile_id: TileId) -> f64 {
+        self.weights.get(&tile_id).cloned().unwrap_or(1.0)
     }
 
     /// Returns the split direction used for splitting up the `rest` of the
@@ -86,7 +109,7 @@ impl SidedLayout {
     /// For directions `Left` and `Right`, this function returns
     /// `SplitDirection::Horizontal`. For directions `Up` and `Down`, this
     /// function returns `SplitDirection::Vertical`.
-    fn split_direction(self) -> SplitDirection {
+    fn split_direction(&self) -> SplitDirection {
         match self.side {
             Direction::Left | Direction::Right => SplitDirection::Horizontal,
             Direction::Up | Direction::Down => SplitDirection::Vertical,
@@ -96,11 +119,27 @@ impl SidedLayout {
     // The logic behind sided layout.
     #[tracing::instrument(skip(self, update_info))]
     fn layout0<'a>(&mut self, update_info: &'a mut LayoutUpdateInfo<'a>) {
-        let display_ = update_info.workspace_bbox;
-        let tiles: &mut Vec<Tile> = update_info.tiles;
+        let gaps = update_info.gaps;
+        let display_ = gaps.apply_outer(update_info.workspace_bbox);
+        let main_index = match update_info.main_tile_index(self.main) {
+            Some(index) => index,
+            None => return,
+        };
+        let tiles = &mut update_info.tiles;
         trace!("Aligning {} tiles inside of {:?}", tiles.len(), display_);
 
-        // Create bounding boxes for the focused tile and the rest of the tiles.
+        // Forget the weights of tiles that are gone.
+        self.weights
+            .retain(|tile_id, _| tiles.iter().any(|tile| tile.id == *tile_id));
+
+        // A single tile takes the whole space.
+        if tiles.len() == 1 {
+            tiles[0].bbox = gaps.apply_inner(display_, display_);
+            trace!("Applied bounding box for single tile@{}", tiles[0].id);
+            return;
+        }
+
+        // Create bounding boxes for the main tile and the rest of the tiles.
         let sided_box = self.bbox_for_side(display_);
         let (side_bbox, rest_bbox) = (sided_box.sided(), sided_box.rest());
         trace!(side_bbox = %side_bbox, rest_bbox = %rest_bbox, "Calculated bounding boxes");
@@ -111,27 +150,96 @@ impl SidedLayout {
             "Splitting remaining space between {} tiles",
             number_of_non_sided_bboxes
         );
-        let child_bboxes = BBox::equal_split(
-            rest_bbox,
-            number_of_non_sided_bboxes,
-            self.split_direction(),
-        );
-
-        // Find the newest tile.
-        let max_id_tile_id = tiles.iter().max_by_key(|t| t.id).unwrap().id;
+        let weights: Vec<f64> = tiles
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| index != main_index)
+            .map(|(_, tile)| self.weight(tile.id))
+            .collect();
+        let mut child_bboxes =
+            BBox::weighted_split(rest_bbox, &weights, self.split_direction()).into_iter();
 
+        // The remaining tiles keep their order inside of the rest area.
         debug!("Applying new bounding boxes");
         for (index, tile) in tiles.iter_mut().enumerate() {
-            if tile.id == max_id_tile_id {
-                tile.bbox = side_bbox;
+            if index == main_index {
+                tile.bbox = gaps.apply_inner(display_, side_bbox);
                 trace!("Applied bounding box for side tile@{}", tile.id);
-            } else {
-                tile.bbox = child_bboxes[index];
+            } else if let Some(child_bbox) = child_bboxes.next() {
+                tile.bbox = gaps.apply_inner(display_, child_bbox);
                 trace!("Applied bounding box for normal tile@{}", tile.id);
             }
         }
         trace!("Applied new bounding boxes");
     }
+
+    // Moves the edge of the focused tile that faces the given direction.
+    fn resize(
+        &mut self,
+        direction: Direction,
+        amount: ResizeAmount,
+        info: &LayoutMessageInfo,
+    ) -> bool {
+        let (focused_index, main_index) =
+            match (info.focused_index(), info.main_tile_index(self.main)) {
+                (Some(focused_index), Some(main_index)) if info.tiles.len() > 1 => {
+                    (focused_index, main_index)
+                }
+                _ => return false,
+            };
+        let delta = info.resize_ratio(direction, amount);
+
+        if focused_index == main_index {
+            // The main tile only borders the remaining tiles.
+            if direction != self.side.opposite() {
+                return false;
+            }
+            self.ratio = clamp_ratio(self.ratio + delta);
+            return true;
+        }
+
+        if direction == self.side {
+            self.ratio = clamp_ratio(self.ratio - delta);
+            return true;
+        } else if direction == self.side.opposite() {
+            return false;
+        }
+
+        // The tile is resized inside of the remaining tiles by moving weight
+        // from its neighbour to it.
+        let rest: Vec<TileId> = info
+            .tiles
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| index != main_index)
+            .map(|(_, tile)| tile.id)
+            .collect();
+        let position = match rest.iter().position(|&id| Some(id) == info.focused_tile_id) {
+            Some(position) => position,
+            None => return false,
+        };
+        let neighbour = match direction {
+            Direction::Left | Direction::Up if position > 0 => rest[position - 1],
+            Direction::Right | Direction::Down if position + 1 < rest.len() => rest[position + 1],
+            _ => return false,
+        };
+
+        let focused_tile_id = rest[position];
+        let total: f64 = rest.iter().map(|&id| self.weight(id)).sum();
+        let min_weight = MIN_RATIO * total;
+        let (weight, neighbour_weight) = (self.weight(focused_tile_id), self.weight(neighbour));
+        let delta = (delta * total)
+            .min(neighbour_weight - min_weight)
+            .max(min_weight - weight);
+        self.weights.insert(focused_tile_id, weight + delta);
+        self.weights.insert(neighbour, neighbour_weight - delta);
+        true
+    }
+}
+
+// Returns the default share of the main tile.
+fn default_ratio() -> f64 {
+    0.5
 }
 
 #[typetag::serde]
@@ -165,6 +273,26 @@ impl Layout for SidedLayout {
             trace!("Layed out tiles. Marking as clean")
         }
     }
+
+    fn handle_message(&mut self, message: &LayoutMessage, info: &LayoutMessageInfo) -> bool {
+        let handled = match *message {
+            LayoutMessage::GrowMaster(delta) => {
+                self.ratio = clamp_ratio(self.ratio + delta);
+                true
+            }
+            LayoutMessage::ShrinkMaster(delta) => {
+                self.ratio = clamp_ratio(self.ratio - delta);
+                true
+            }
+            LayoutMessage::Resize(direction, amount) => self.resize(direction, amount, info),
+            _ => false,
+        };
+
+        if handled {
+            self.invalidate();
+        }
+        handled
+    }
 }
 
 #[cfg(test)]
@@ -172,10 +300,13 @@ mod tests {
     use crate::{
         bbox::BBox,
         display::Display,
+        layout::{sided_layout::SidedLayout, tests::bboxes, MainTile, ResizeAmount},
         tile::{Tile, TileId},
+        util::Direction,
         window::Window,
         workspace::Workspace,
     };
+    use pretty_assertions::assert_eq;
 
     pub fn init_tracing() {
         use tracing::Level;
@@ -201,16 +332,125 @@ mod tests {
     fn test_layout() {
         //init_tracing();
 
+        let mut display = Display::new();
+        display.bbox = BBox::with(0, 0, 1920, 1080);
+
+        // The last added tile is focused and becomes the main tile.
+        let mut workspace = Workspace::with(0, display.clone());
+        workspace.layout = Box::new(SidedLayout::new());
+        for tile in generate_tiles(3) {
+            workspace.add_tile(tile).unwrap();
+        }
+        workspace.layout();
+
+        let expected = vec![
+            BBox::with(960, 0, 960, 540),
+            BBox::with(960, 540, 960, 540),
+            BBox::with(0, 0, 960, 1080),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+
+        // The first tile is the main tile, regardless of the focus.
+        let mut workspace = Workspace::with(0, display);
+        let mut layout = SidedLayout::new();
+        layout.main = MainTile::Master;
+        workspace.layout = Box::new(layout);
+        for tile in generate_tiles(3) {
+            workspace.add_tile(tile).unwrap();
+        }
+        workspace.layout();
+
+        let expected = vec![
+            BBox::with(0, 0, 960, 1080),
+            BBox::with(960, 0, 960, 540),
+            BBox::with(960, 540, 960, 540),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_layout_focused_tile() {
         let mut display = Display::new();
         display.bbox = BBox::with(0, 0, 1920, 1080);
         let mut workspace = Workspace::with(0, display);
         for tile in generate_tiles(3) {
-            workspace.add_tile(tile);
+            workspace.add_tile(tile).unwrap();
         }
+        workspace.layout();
 
-        workspace.layout.invalidate();
+        // The main area follows the focus.
+        workspace.focus_tile(1);
+        workspace.layout();
+
+        let expected = vec![
+            BBox::with(960, 0, 960, 540),
+            BBox::with(0, 0, 960, 1080),
+            BBox::with(960, 540, 960, 540),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+
+        workspace.focus_tile(0);
+        workspace.layout();
+
+        let expected = vec![
+            BBox::with(0, 0, 960, 1080),
+            BBox::with(960, 0, 960, 540),
+            BBox::with(960, 540, 960, 540),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_layout_master_tile() {
+        let mut display = Display::new();
+        display.bbox = BBox::with(0, 0, 1920, 1080);
+        let mut workspace = Workspace::with(0, display);
+        let mut layout = SidedLayout::with(Direction::Right);
+        layout.main = MainTile::Master;
+        workspace.layout = Box::new(layout);
+        for tile in generate_tiles(3) {
+            workspace.add_tile(tile).unwrap();
+        }
+
+        workspace.layout();
+
+        let expected = vec![
+            BBox::with(960, 0, 960, 1080),
+            BBox::with(0, 0, 960, 540),
+            BBox::with(0, 540, 960, 540),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut display = Display::new();
+        display.bbox = BBox::with(0, 0, 1000, 1000);
+        let mut workspace = Workspace::with(0, display);
+        let mut layout = SidedLayout::new();
+        layout.main = MainTile::Master;
+        workspace.layout = Box::new(layout);
+        for tile in generate_tiles(3) {
+            workspace.add_tile(tile).unwrap();
+        }
+
+        workspace.focus_tile(0);
+        assert!(workspace.resize_focused(Direction::Right, ResizeAmount::Pixels(100)));
+        assert!(!workspace.resize_focused(Direction::Left, ResizeAmount::Pixels(100)));
+        workspace.focus_tile(1);
+        assert!(workspace.resize_focused(Direction::Down, ResizeAmount::Percent(10.0)));
         workspace.layout();
 
-        assert_eq!(true, true);
+        let expected = vec![
+            BBox::with(0, 0, 600, 1000),
+            BBox::with(600, 0, 400, 600),
+            BBox::with(600, 600, 400, 400),
+        ];
+        assert_eq!(expected, bboxes(&workspace));
+
+        // The sizes survive relayouts.
+        workspace.layout.invalidate();
+        workspace.layout();
+        assert_eq!(expected, bboxes(&workspace));
     }
 }
@@ -10,6 +10,8 @@ pub mod bbox;
 pub mod config;
 mod debug;
 pub mod display;
+pub mod error;
+pub mod id;
 pub mod layout;
 pub mod manager;
 pub mod tile;
@@ -18,6 +20,7 @@ pub mod window;
 pub mod workspace;
 
 pub use config::toml;
+pub use error::{Error, Result};
 pub use layout::DynClone;
 
 #[cfg(test)]
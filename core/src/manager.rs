@@ -1,7 +1,55 @@
 //! The `Manager` is responsible for managing all workspaces and keeping track
 //! of the active.
+//!
+//! Each display shows exactly one of its workspaces at a time. The focused
+//! workspace is the visible workspace of the focused display.
 
-use crate::workspace::{Workspace, WorkspaceId};
+use crate::{
+    bbox::BBox,
+    config::Config,
+    display::{Display, DisplayId, DisplayTopology, Reservation, ReservationId},
+    error::{Error, IdKind, Result},
+    id::IdAllocator,
+    tile::{Tile, TileId},
+    util::Direction,
+    window::{Window, WindowHandle, WindowId},
+    workspace::{Workspace, WorkspaceId},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// The workspace a tile is moved to.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum WorkspaceTarget {
+    /// The workspace with the given id.
+    Id(WorkspaceId),
+    /// The workspace that follows the tile's workspace. The last workspace is
+    /// followed by the first one.
+    Next,
+    /// The workspace that precedes the tile's workspace. The first workspace
+    /// is preceded by the last one.
+    Previous,
+    /// The visible workspace of the neighbouring display in the given
+    /// direction.
+    Display(Direction),
+}
+
+/// A change of the visibility of a workspace that a backend has to apply.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum VisibilityChange {
+    /// The workspace is shown on the given display.
+    Show {
+        /// The id of the workspace.
+        workspace_id: WorkspaceId,
+        /// The id of the display.
+        display_id: DisplayId,
+    },
+    /// The workspace is hidden.
+    Hide {
+        /// The id of the workspace.
+        workspace_id: WorkspaceId,
+    },
+}
 
 /// A manager.
 #[derive(Clone, Debug, Default)]
@@ -9,7 +57,38 @@ pub struct Manager {
     /// The id of the focused `Workspace`. `None` if no `Workspace` is focused.
     pub focused_workspace_id: Option<WorkspaceId>,
     /// The list of workspaces.
+    ///
+    /// The ids of workspaces that are pushed here directly are not reserved.
+    /// Pass them to `Manager::with` or use `Manager::create_workspace`
+    /// instead.
     pub workspaces: Vec<Workspace>,
+    /// The tiles that got focused across all workspaces, from the most
+    /// recently to the least recently focused one.
+    focus_history: Vec<(WorkspaceId, TileId)>,
+    /// The position inside of the focus history while cycling through it.
+    /// `None` if no cycle is in progress.
+    cycle_index: Option<usize>,
+    /// Whether switching to the focused workspace switches back to the
+    /// previously focused one.
+    pub back_and_forth: bool,
+    /// The id of the workspace that was focused before the focused one.
+    previous_workspace_id: Option<WorkspaceId>,
+    /// The id of the visible workspace of each display.
+    visible_workspaces: HashMap<DisplayId, WorkspaceId>,
+    /// The visibility changes that have not been applied by the backend yet.
+    visibility_changes: Vec<VisibilityChange>,
+    /// The arrangement of the displays known to the manager, including the
+    /// ones without any workspace.
+    topology: DisplayTopology,
+    /// The applied configuration.
+    config: Config,
+    /// The allocator of tile, window, workspace and reservation ids.
+    ids: IdAllocator,
+    /// The ids of all windows, by their OS' window handle.
+    window_ids: HashMap<WindowHandle, WindowId>,
+    /// The ids of the workspaces that got created on demand. Only these
+    /// workspaces are destroyed once they are empty and hidden.
+    on_demand_workspace_ids: HashSet<WorkspaceId>,
 }
 
 impl Manager {
@@ -22,14 +101,335 @@ impl Manager {
     ///
     /// # Arguments
     ///
-    /// - `workspaces`: The `Workspace`s managed by this manager.
+    /// - `workspaces`: The `Workspace`s managed by this manager. The ids of the
+    ///   workspaces, their tiles, windows and reserved display areas are
+    ///   reserved.
     pub fn with(workspaces: Vec<Workspace>) -> Self {
-        Self {
-            workspaces,
-            ..Self::default()
+        let mut manager = Self::default();
+        for workspace in &workspaces {
+            if manager.topology.display(workspace.display.id).is_none() {
+                manager.topology.insert(workspace.display.clone());
+            }
+            for (id, _) in workspace.display.reservations() {
+                manager.ids.reserve_reservation_id(id);
+            }
+            manager.ids.reserve_workspace_id(workspace.id);
+            for tile in workspace.iter() {
+                manager.ids.reserve_tile_id(tile.id);
+                manager.ids.reserve_window_id(tile.window.id);
+                manager
+                    .window_ids
+                    .insert(tile.window.handle, tile.window.id);
+            }
+        }
+        manager.workspaces = workspaces;
+
+        manager
+    }
+
+    /// Applies a configuration to all workspaces.
+    ///
+    /// Configured workspaces that already exist take over their label and
+    /// whether they are persistent. Persistent workspaces that do not exist
+    /// yet are created.
+    ///
+    /// # Arguments
+    ///
+    /// - `config`: The configuration to apply.
+    pub fn apply_config(&mut self, config: &Config) {
+        self.config = config.clone();
+        for workspace in &mut self.workspaces {
+            workspace.set_gaps(config.gaps);
+            if let Some(workspace_config) = config.workspace(&workspace.name) {
+                workspace.label = workspace_config.label.clone();
+                workspace.persistent = workspace_config.persistent;
+            }
+        }
+
+        for workspace_config in config.workspaces.iter().filter(|config| config.persistent) {
+            self.create_workspace(&workspace_config.name, None);
+        }
+    }
+
+    /// Adds a display or updates the bounding box of a known one. Workspaces
+    /// on an updated display are laid out again. The ids of the display's
+    /// reserved areas are reserved.
+    ///
+    /// # Arguments
+    ///
+    /// - `display`: The display to add.
+    pub fn add_display(&mut self, display: Display) {
+        for (id, _) in display.reservations() {
+            self.ids.reserve_reservation_id(id);
+        }
+        for workspace in &mut self.workspaces {
+            if workspace.display.id == display.id && workspace.display != display {
+                workspace.set_display(display.clone());
+                workspace.layout();
+            }
+        }
+
+        self.topology.insert(display);
+    }
+
+    /// Reserves an area along an edge of a display that tiles must not cover,
+    /// e.g. for a status bar. Workspaces on the display are laid out again.
+    ///
+    /// # Arguments
+    ///
+    /// - `display_id`: The id of the display.
+    /// - `reservation`: The area to reserve.
+    ///
+    /// # Returns
+    ///
+    /// The id of the reservation, which is used to release it again. `None`
+    /// if the display does not exist or if all reservation ids are in use.
+    pub fn reserve_area(
+        &mut self,
+        display_id: DisplayId,
+        reservation: Reservation,
+    ) -> Option<ReservationId> {
+        let mut display = self
+            .displays()
+            .into_iter()
+            .find(|display| display.id == display_id)?;
+        let id = self.ids.reservation_id().ok()?;
+        display.reserve(id, reservation);
+        self.add_display(display);
+        Some(id)
+    }
+
+    /// Releases a reserved area. Workspaces on its display are laid out
+    /// again.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Reservation)` if the area was reserved, `None` otherwise.
+    pub fn release_area(&mut self, reservation_id: ReservationId) -> Option<Reservation> {
+        let mut display = self
+            .displays()
+            .into_iter()
+            .find(|display| display.reservations().any(|(id, _)| id == reservation_id))?;
+        let reservation = display.release(reservation_id);
+        self.add_display(display);
+        reservation
+    }
+
+    /// Creates a named workspace.
+    ///
+    /// The workspace is created on the given display. If no display is given,
+    /// the display assigned by the configuration or the focused display is
+    /// used. The label and whether the workspace is persistent are taken from
+    /// the configuration.
+    ///
+    /// # Arguments
+    ///
+    /// - `name`: The name of the workspace.
+    /// - `display_id`: The id of the display to create the workspace on.
+    ///
+    /// # Returns
+    ///
+    /// The id of the created workspace. If a workspace with the given name
+    /// already exists, its id is returned instead. `None` if the display is
+    /// unknown or if all workspace ids are in use.
+    pub fn create_workspace(
+        &mut self,
+        name: &str,
+        display_id: Option<DisplayId>,
+    ) -> Option<WorkspaceId> {
+        if let Some(workspace) = self.workspace_by_name(name) {
+            return Some(workspace.id);
+        }
+
+        let workspace_config = self.config.workspace(name).cloned().unwrap_or_default();
+        let display_id = display_id
+            .or(workspace_config.display)
+            .or_else(|| self.focused_display_id())
+            .or_else(|| self.topology.primary().map(|display| display.id))?;
+        let display = self
+            .displays()
+            .into_iter()
+            .find(|display| display.id == display_id)?;
+
+        let workspace_id = self.ids.workspace_id().ok()?;
+        let mut workspace = Workspace::with(workspace_id, display);
+        workspace.name = name.to_string();
+        workspace.label = workspace_config.label;
+        workspace.persistent = workspace_config.persistent;
+        workspace.set_gaps(self.config.gaps);
+        self.workspaces.push(workspace);
+        self.on_demand_workspace_ids.insert(workspace_id);
+        Some(workspace_id)
+    }
+
+    /// Switches to the workspace with the given name, creating it if it does
+    /// not exist yet.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the focused workspace changed, `false` otherwise.
+    pub fn switch_to_workspace_by_name(&mut self, name: &str) -> bool {
+        match self.create_workspace(name, None) {
+            Some(workspace_id) => self.switch_to_workspace(workspace_id),
+            None => false,
+        }
+    }
+
+    /// Renames a workspace.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the workspace got renamed, `false` if it does not exist or if
+    /// another workspace already has the given name.
+    pub fn rename_workspace(&mut self, workspace_id: WorkspaceId, name: &str) -> bool {
+        if self
+            .workspace_by_name(name)
+            .map_or(false, |workspace| workspace.id != workspace_id)
+        {
+            return false;
+        }
+
+        match self
+            .workspaces
+            .iter_mut()
+            .find(|workspace| workspace.id == workspace_id)
+        {
+            Some(workspace) => {
+                workspace.name = name.to_string();
+                true
+            }
+            None => false,
         }
     }
 
+    /// Creates a tile for a new window and adds it to a workspace. The tile and
+    /// the window get unique ids.
+    ///
+    /// # Arguments
+    ///
+    /// - `workspace_id`: The id of the workspace to add the tile to.
+    /// - `handle`: The OS' window handle.
+    /// - `original_bbox`: The bounding box of the window before it is managed.
+    ///
+    /// # Returns
+    ///
+    /// The id of the created tile, or an error if the workspace does not exist,
+    /// if the window is already managed or if all tile or window ids are in
+    /// use.
+    pub fn add_window(
+        &mut self,
+        workspace_id: WorkspaceId,
+        handle: WindowHandle,
+        original_bbox: BBox,
+    ) -> Result<TileId> {
+        let window = Window::with(self.ids.window_id()?, handle, original_bbox);
+        let tile = Tile::with(self.ids.tile_id()?, original_bbox, window);
+        self.add_tile(workspace_id, tile)?;
+        Ok(tile.id)
+    }
+
+    /// Adds a tile to a workspace and focuses it there.
+    ///
+    /// # Arguments
+    ///
+    /// - `workspace_id`: The id of the workspace to add the tile to.
+    /// - `tile`: The tile to add.
+    ///
+    /// # Returns
+    ///
+    /// An error if the workspace does not exist or if the id of the tile, the
+    /// id of its window or its window handle is already in use.
+    pub fn add_tile(&mut self, workspace_id: WorkspaceId, tile: Tile) -> Result<()> {
+        if self.window_ids.contains_key(&tile.window.handle) {
+            return Err(Error::DuplicateId {
+                kind: IdKind::WindowHandle,
+                id: i64::from(tile.window.handle),
+            });
+        }
+        for other in self
+            .workspaces
+            .iter()
+            .flat_map(|workspace| workspace.iter())
+        {
+            if other.id == tile.id {
+                return Err(Error::DuplicateId {
+                    kind: IdKind::Tile,
+                    id: i64::from(tile.id),
+                });
+            }
+            if other.window.id == tile.window.id {
+                return Err(Error::DuplicateId {
+                    kind: IdKind::Window,
+                    id: i64::from(tile.window.id),
+                });
+            }
+        }
+
+        let workspace = self
+            .workspaces
+            .iter_mut()
+            .find(|workspace| workspace.id == workspace_id)
+            .ok_or(Error::UnknownWorkspace(workspace_id))?;
+        workspace.add_tile(tile)?;
+        self.ids.reserve_tile_id(tile.id);
+        self.ids.reserve_window_id(tile.window.id);
+        self.window_ids.insert(tile.window.handle, tile.window.id);
+        Ok(())
+    }
+
+    /// Removes a tile from the workspace it is in.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Tile)` if the tile exists, `None` otherwise.
+    pub fn remove_tile(&mut self, tile_id: TileId) -> Option<Tile> {
+        let workspace = self
+            .workspaces
+            .iter_mut()
+            .find(|workspace| workspace.tile_by_id(tile_id).is_some())?;
+        let tile = workspace.take_tile(tile_id)?;
+        if self.window_ids.get(&tile.window.handle) == Some(&tile.window.id) {
+            self.window_ids.remove(&tile.window.handle);
+        }
+
+        Some(tile)
+    }
+
+    /// Returns the id of the window with the given OS' window handle.
+    ///
+    /// # Returns
+    ///
+    /// `Some(WindowId)` if the window is managed, `None` otherwise.
+    pub fn window_id(&self, handle: WindowHandle) -> Option<WindowId> {
+        self.window_ids.get(&handle).cloned()
+    }
+
+    /// Returns the tile that displays the window with the given id, together
+    /// with the id of its workspace.
+    ///
+    /// # Returns
+    ///
+    /// `Some((WorkspaceId, &Tile))` if the window is managed, `None` otherwise.
+    pub fn tile_by_window_id(&self, window_id: WindowId) -> Option<(WorkspaceId, &Tile)> {
+        self.workspaces.iter().find_map(|workspace| {
+            workspace
+                .iter()
+                .find(|tile| tile.window.id == window_id)
+                .map(|tile| (workspace.id, tile))
+        })
+    }
+
+    /// Returns the workspace with the given name.
+    ///
+    /// # Returns
+    ///
+    /// `Some(&Workspace)` if such a workspace exists, `None` otherwise.
+    pub fn workspace_by_name(&self, name: &str) -> Option<&Workspace> {
+        self.workspaces
+            .iter()
+            .find(|workspace| workspace.name == name)
+    }
+
     /// Returns a reference to the focused workspace.
     ///
     /// # Returns
@@ -67,4 +467,919 @@ impl Manager {
 
         None
     }
+
+    /// Focuses the tile that is nearest to the focused tile in the given
+    /// direction.
+    ///
+    /// If the focused workspace has no tile in that direction, the focus moves
+    /// to the visible workspace of the neighbouring display in that direction.
+    /// There, the tile nearest to the previously focused tile gets focused.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the focus changed, `false` otherwise.
+    pub fn focus_direction(&mut self, direction: Direction) -> bool {
+        let (origin, display) = match self.focused_workspace_mut() {
+            Some(workspace) => {
+                if workspace.focus_direction(direction) {
+                    self.record_focus();
+                    return true;
+                }
+                let origin = workspace
+                    .focused_tile()
+                    .map_or(workspace.display.bbox, |tile| tile.bbox);
+                (origin, workspace.display.clone())
+            }
+            None => return false,
+        };
+
+        let workspace_id = match self
+            .display_in_direction(&display, direction)
+            .and_then(|display| self.visible_workspace_id(display.id))
+        {
+            Some(workspace_id) => workspace_id,
+            None => return false,
+        };
+
+        self.set_focused_workspace(workspace_id);
+        if let Some(workspace) = self.focused_workspace_mut() {
+            if let Some(tile_id) = workspace.tile_in_direction_of(origin, direction) {
+                workspace.focus_tile(tile_id);
+            }
+        }
+        self.record_focus();
+        true
+    }
+
+    /// Moves a tile to another workspace.
+    ///
+    /// The tile is focused inside of the target workspace, while the source
+    /// workspace falls back to its most recently focused tile. Both workspaces
+    /// are laid out again. Floating tiles keep their position relative to the
+    /// display.
+    ///
+    /// # Arguments
+    ///
+    /// - `tile_id`: The id of the tile to move.
+    /// - `target`: The workspace to move the tile to.
+    /// - `follow`: Whether the focus follows the tile to the target workspace.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if the tile got moved, `Ok(false)` if the tile or the target
+    /// workspace does not exist or if the tile already is inside of the target
+    /// workspace.
+    ///
+    /// An error if the target workspace rejects the tile, e.g. because it
+    /// holds a tile with the same id. Rejected tiles stay in their workspace
+    /// and nothing changes.
+    pub fn move_tile(
+        &mut self,
+        tile_id: TileId,
+        target: WorkspaceTarget,
+        follow: bool,
+    ) -> Result<bool> {
+        let source_index = match self
+            .workspaces
+            .iter()
+            .position(|workspace| workspace.tile_by_id(tile_id).is_some())
+        {
+            Some(index) => index,
+            None => return Ok(false),
+        };
+        let target_index = match self.target_index(source_index, target) {
+            Some(index) if index != source_index => index,
+            _ => return Ok(false),
+        };
+        if self.workspaces[target_index].tile_by_id(tile_id).is_some() {
+            return Err(Error::DuplicateId {
+                kind: IdKind::Tile,
+                id: i64::from(tile_id),
+            });
+        }
+
+        let source = &mut self.workspaces[source_index];
+        let source_id = source.id;
+        let source_origin = source.display.bbox;
+        let index = match source.iter().position(|tile| tile.id == tile_id) {
+            Some(index) => index,
+            None => return Ok(false),
+        };
+        let original = match source.take_tile(tile_id) {
+            Some(tile) => tile,
+            None => return Ok(false),
+        };
+
+        let target = &mut self.workspaces[target_index];
+        let target_id = target.id;
+        let mut tile = original;
+        if tile.floating {
+            tile.bbox = tile.bbox.translate(
+                target.display.bbox.x.saturating_sub(source_origin.x),
+                target.display.bbox.y.saturating_sub(source_origin.y),
+            );
+        }
+        if let Err(error) = target.add_tile(tile) {
+            // Put the tile back, so it does not get lost.
+            let source = &mut self.workspaces[source_index];
+            source.add_tile(original)?;
+            source.move_tile(tile_id, index);
+            return Err(error);
+        }
+        self.workspaces[source_index].layout();
+        self.workspaces[target_index].layout();
+
+        for entry in &mut self.focus_history {
+            if *entry == (source_id, tile_id) {
+                *entry = (target_id, tile_id);
+            }
+        }
+        if follow {
+            self.focus_tile(target_id, tile_id);
+        }
+        Ok(true)
+    }
+
+    /// Moves the focused tile of the focused workspace to another workspace.
+    ///
+    /// See `move_tile` for details.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if the tile got moved, `Ok(false)` if there is nothing to
+    /// move and an error if the target workspace rejects the tile.
+    pub fn move_focused(&mut self, target: WorkspaceTarget, follow: bool) -> Result<bool> {
+        match self
+            .focused_workspace()
+            .and_then(|workspace| workspace.focused_tile_id)
+        {
+            Some(tile_id) => self.move_tile(tile_id, target, follow),
+            None => Ok(false),
+        }
+    }
+
+    // Returns the index of the targeted workspace, relative to the workspace
+    // at the given index.
+    fn target_index(&self, index: usize, target: WorkspaceTarget) -> Option<usize> {
+        let length = self.workspaces.len();
+        match target {
+            WorkspaceTarget::Id(id) => self
+                .workspaces
+                .iter()
+                .position(|workspace| workspace.id == id),
+            WorkspaceTarget::Next => Some((index + 1) % length),
+            WorkspaceTarget::Previous => Some((index + length - 1) % length),
+            WorkspaceTarget::Display(direction) => {
+                let display = self.workspaces[index].display.clone();
+                let workspace_id = self
+                    .display_in_direction(&display, direction)
+                    .and_then(|display| self.visible_workspace_id(display.id))?;
+                self.workspaces
+                    .iter()
+                    .position(|workspace| workspace.id == workspace_id)
+            }
+        }
+    }
+
+    /// Returns the arrangement of the displays.
+    pub fn topology(&self) -> &DisplayTopology {
+        &self.topology
+    }
+
+    /// Makes a display the primary one.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the display is known, `false` otherwise.
+    pub fn set_primary_display(&mut self, display_id: DisplayId) -> bool {
+        self.topology.set_primary(display_id)
+    }
+
+    /// Returns the display that the window with the given id was on before it
+    /// got managed, based on the window's original bounding box.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the window is unknown or if its original bounding box does
+    /// not overlap any display.
+    pub fn display_of_window(&self, window_id: WindowId) -> Option<&Display> {
+        let (_, tile) = self.tile_by_window_id(window_id)?;
+        self.topology.display_containing(tile.window.original_bbox)
+    }
+
+    /// Returns all displays. Displays of the topology come first, followed by
+    /// the displays of workspaces that were added without using the manager,
+    /// in the order of the workspaces on them.
+    pub fn displays(&self) -> Vec<Display> {
+        let mut displays = self.topology.displays().to_vec();
+        for workspace in &self.workspaces {
+            if displays
+                .iter()
+                .all(|display| display.id != workspace.display.id)
+            {
+                displays.push(workspace.display.clone());
+            }
+        }
+
+        displays
+    }
+
+    /// Returns the id of the focused display.
+    ///
+    /// # Returns
+    ///
+    /// `Some(DisplayId)` if a workspace is focused, `None` otherwise.
+    pub fn focused_display_id(&self) -> Option<DisplayId> {
+        self.focused_workspace()
+            .map(|workspace| workspace.display.id)
+    }
+
+    /// Returns the id of the workspace that is visible on the given display.
+    ///
+    /// If no workspace has been shown on the display yet, the focused
+    /// workspace or, if it is on another display, the first workspace on the
+    /// display is visible.
+    ///
+    /// # Returns
+    ///
+    /// `Some(WorkspaceId)` if the display has workspaces, `None` otherwise.
+    pub fn visible_workspace_id(&self, display_id: DisplayId) -> Option<WorkspaceId> {
+        let is_on_display = |workspace_id: WorkspaceId| {
+            self.workspaces
+                .iter()
+                .any(|workspace| workspace.id == workspace_id && workspace.display.id == display_id)
+        };
+
+        self.visible_workspaces
+            .get(&display_id)
+            .cloned()
+            .filter(|&workspace_id| is_on_display(workspace_id))
+            .or_else(|| self.focused_workspace_id.filter(|&id| is_on_display(id)))
+            .or_else(|| {
+                self.workspaces
+                    .iter()
+                    .find(|workspace| workspace.display.id == display_id)
+                    .map(|workspace| workspace.id)
+            })
+    }
+
+    /// Returns whether the workspace is visible on its display.
+    pub fn is_workspace_visible(&self, workspace_id: WorkspaceId) -> bool {
+        self.workspaces
+            .iter()
+            .find(|workspace| workspace.id == workspace_id)
+            .map_or(false, |workspace| {
+                self.visible_workspace_id(workspace.display.id) == Some(workspace_id)
+            })
+    }
+
+    /// Focuses the visible workspace of the given display.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the display has workspaces, `false` otherwise.
+    pub fn focus_display(&mut self, display_id: DisplayId) -> bool {
+        match self.visible_workspace_id(display_id) {
+            Some(workspace_id) => self.switch_to_workspace(workspace_id),
+            None => false,
+        }
+    }
+
+    /// Switches to a workspace by showing it on its display and focusing it.
+    /// The workspace that was visible on that display before is hidden.
+    ///
+    /// If the workspace is already focused and `back_and_forth` is enabled,
+    /// the previously focused workspace is switched to instead.
+    ///
+    /// # Arguments
+    ///
+    /// - `workspace_id`: The id of the workspace to switch to.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the focused workspace changed, `false` otherwise.
+    pub fn switch_to_workspace(&mut self, workspace_id: WorkspaceId) -> bool {
+        if self.focused_workspace_id == Some(workspace_id) {
+            return self.back_and_forth && self.switch_back_and_forth();
+        }
+        if !self.show_workspace(workspace_id) {
+            return false;
+        }
+
+        self.set_focused_workspace(workspace_id);
+        self.record_focus();
+        true
+    }
+
+    /// Switches to the workspace that was focused before the focused one.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the focused workspace changed, `false` otherwise.
+    pub fn switch_back_and_forth(&mut self) -> bool {
+        match self.previous_workspace_id {
+            Some(workspace_id) if self.focused_workspace_id != Some(workspace_id) => {
+                self.switch_to_workspace(workspace_id)
+            }
+            _ => false,
+        }
+    }
+
+    /// Swaps the visible workspaces of two displays. Both workspaces are moved
+    /// to the other display and laid out again. The focus stays on the same
+    /// workspace.
+    ///
+    /// # Returns
+    ///
+    /// `true` if both displays have a visible workspace, `false` otherwise.
+    pub fn swap_visible_workspaces(&mut self, first: DisplayId, second: DisplayId) -> bool {
+        if first == second {
+            return false;
+        }
+        let (first_workspace_id, second_workspace_id) = match (
+            self.visible_workspace_id(first),
+            self.visible_workspace_id(second),
+        ) {
+            (Some(first), Some(second)) => (first, second),
+            _ => return false,
+        };
+        let first_index = self.workspace_index(first_workspace_id);
+        let second_index = self.workspace_index(second_workspace_id);
+        let (first_index, second_index) = match (first_index, second_index) {
+            (Some(first), Some(second)) => (first, second),
+            _ => return false,
+        };
+
+        let first_display = self.workspaces[first_index].display.clone();
+        let second_display = self.workspaces[second_index].display.clone();
+        self.workspaces[first_index].set_display(second_display);
+        self.workspaces[first_index].layout();
+        self.workspaces[second_index].set_display(first_display);
+        self.workspaces[second_index].layout();
+
+        self.visible_workspaces.insert(first, second_workspace_id);
+        self.visible_workspaces.insert(second, first_workspace_id);
+        self.visibility_changes.push(VisibilityChange::Show {
+            workspace_id: second_workspace_id,
+            display_id: first,
+        });
+        self.visibility_changes.push(VisibilityChange::Show {
+            workspace_id: first_workspace_id,
+            display_id: second,
+        });
+        true
+    }
+
+    /// Returns all visibility changes that have not been applied yet and
+    /// clears them. Backends call this after each operation and apply the
+    /// changes in order.
+    pub fn take_visibility_changes(&mut self) -> Vec<VisibilityChange> {
+        std::mem::take(&mut self.visibility_changes)
+    }
+
+    // Focuses the workspace. The previously focused workspace is destroyed if
+    // it got created on demand and is empty, hidden and not persistent.
+    fn set_focused_workspace(&mut self, workspace_id: WorkspaceId) {
+        if self.focused_workspace_id == Some(workspace_id) {
+            return;
+        }
+
+        self.previous_workspace_id = self.focused_workspace_id;
+        self.focused_workspace_id = Some(workspace_id);
+        if let Some(previous_workspace_id) = self.previous_workspace_id {
+            let unused = self
+                .on_demand_workspace_ids
+                .contains(&previous_workspace_id)
+                && self.workspaces.iter().any(|workspace| {
+                    workspace.id == previous_workspace_id
+                        && workspace.tiles().is_empty()
+                        && !workspace.persistent
+                });
+            if unused && !self.is_workspace_visible(previous_workspace_id) {
+                self.workspaces
+                    .retain(|workspace| workspace.id != previous_workspace_id);
+                self.on_demand_workspace_ids.remove(&previous_workspace_id);
+                self.previous_workspace_id = None;
+            }
+        }
+    }
+
+    // Returns the index of the workspace with the given id.
+    fn workspace_index(&self, workspace_id: WorkspaceId) -> Option<usize> {
+        self.workspaces
+            .iter()
+            .position(|workspace| workspace.id == workspace_id)
+    }
+
+    // Returns the display that lies next to the given display in the given
+    // direction.
+    fn display_in_direction(&self, display: &Display, direction: Direction) -> Option<Display> {
+        self.topology.neighbor(display.id, direction).cloned()
+    }
+
+    // Makes the workspace the visible one of its display and lays it out.
+    //
+    // Returns `false` if the workspace does not exist.
+    fn show_workspace(&mut self, workspace_id: WorkspaceId) -> bool {
+        let index = match self.workspace_index(workspace_id) {
+            Some(index) => index,
+            None => return false,
+        };
+        let display_id = self.workspaces[index].display.id;
+
+        let visible_workspace_id = self.visible_workspace_id(display_id);
+        if visible_workspace_id != Some(workspace_id) {
+            if let Some(visible_workspace_id) = visible_workspace_id {
+                self.visibility_changes.push(VisibilityChange::Hide {
+                    workspace_id: visible_workspace_id,
+                });
+            }
+            self.visibility_changes.push(VisibilityChange::Show {
+                workspace_id,
+                display_id,
+            });
+        }
+        self.visible_workspaces.insert(display_id, workspace_id);
+        self.workspaces[index].layout();
+        true
+    }
+
+    /// Focuses a tile inside of a workspace and makes the workspace the
+    /// focused one.
+    ///
+    /// # Arguments
+    ///
+    /// - `workspace_id`: The id of the workspace holding the tile.
+    /// - `tile_id`: The id of the tile to focus.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the tile exists, `false` otherwise.
+    pub fn focus_tile(&mut self, workspace_id: WorkspaceId, tile_id: TileId) -> bool {
+        if !self.focus_entry(workspace_id, tile_id) {
+            return false;
+        }
+        self.record_focus();
+        true
+    }
+
+    /// Focuses the tile that was focused before the focused tile, regardless
+    /// of the workspace it is in.
+    ///
+    /// # Returns
+    ///
+    /// `true` if such a tile exists, `false` otherwise.
+    pub fn focus_last(&mut self) -> bool {
+        self.prune_focus_history();
+        match self.focus_history.get(1) {
+            Some(&(workspace_id, tile_id)) => self.focus_tile(workspace_id, tile_id),
+            None => false,
+        }
+    }
+
+    /// Cycles through the focus history, like alt-tab does.
+    ///
+    /// The focus history is not reordered while cycling, so consecutive calls
+    /// reach less and less recently focused tiles. The cycle is finished by
+    /// calling `finish_focus_cycle` or by focusing any tile.
+    ///
+    /// # Arguments
+    ///
+    /// - `forward`: If `true`, the next less recently focused tile is focused.
+    ///   If `false`, the next more recently focused one.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the focus changed, `false` otherwise.
+    pub fn cycle_focus(&mut self, forward: bool) -> bool {
+        if self.cycle_index.is_none() {
+            self.prune_focus_history();
+        }
+        let length = self.focus_history.len();
+        if length < 2 {
+            return false;
+        }
+
+        let index = self.cycle_index.unwrap_or(0).min(length - 1);
+        let index = if forward {
+            (index + 1) % length
+        } else {
+            (index + length - 1) % length
+        };
+        let (workspace_id, tile_id) = self.focus_history[index];
+        self.cycle_index = Some(index);
+        self.focus_entry(workspace_id, tile_id)
+    }
+
+    /// Finishes cycling through the focus history. The focused tile becomes
+    /// the most recently focused one.
+    pub fn finish_focus_cycle(&mut self) {
+        if self.cycle_index.take().is_some() {
+            self.record_focus();
+        }
+    }
+
+    /// Returns the tiles that got focused across all workspaces, from the most
+    /// recently to the least recently focused one.
+    pub fn focus_history(&self) -> &[(WorkspaceId, TileId)] {
+        &self.focus_history
+    }
+
+    // Focuses a tile without recording it in the focus history. Hidden
+    // workspaces are shown.
+    fn focus_entry(&mut self, workspace_id: WorkspaceId, tile_id: TileId) -> bool {
+        let focused = self
+            .workspaces
+            .iter_mut()
+            .find(|workspace| workspace.id == workspace_id)
+            .map_or(false, |workspace| workspace.focus_tile(tile_id));
+        if focused {
+            self.show_workspace(workspace_id);
+            self.set_focused_workspace(workspace_id);
+        }
+        focused
+    }
+
+    // Moves the focused tile to the front of the focus history. This finishes
+    // any cycle that is in progress.
+    fn record_focus(&mut self) {
+        self.cycle_index = None;
+        let entry = match self.focused_workspace() {
+            Some(workspace) => match workspace.focused_tile_id {
+                Some(tile_id) => (workspace.id, tile_id),
+                None => return,
+            },
+            None => return,
+        };
+        self.focus_history.retain(|&other| other != entry);
+        self.focus_history.insert(0, entry);
+    }
+
+    // Removes tiles from the focus history that are no longer part of their
+    // workspace.
+    fn prune_focus_history(&mut self) {
+        let workspaces = &self.workspaces;
+        self.focus_history.retain(|&(workspace_id, tile_id)| {
+            workspaces.iter().any(|workspace| {
+                workspace.id == workspace_id && workspace.tile_by_id(tile_id).is_some()
+            })
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bbox::BBox,
+        config::WorkspaceConfig,
+        display::Edge,
+        layout::grid_layout::GridLayout,
+        tile::{Tile, TileId},
+        window::Window,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn workspace_with_tiles(id: WorkspaceId, display: Display, tile_ids: &[TileId]) -> Workspace {
+        let mut workspace = Workspace::with(id, display);
+        workspace.layout = Box::new(GridLayout::new());
+        for &tile_id in tile_ids {
+            workspace
+                .add_tile(Tile::with(tile_id, BBox::new(), Window::new()))
+                .unwrap();
+        }
+        workspace.layout();
+
+        workspace
+    }
+
+    #[test]
+    fn test_focus_direction_crosses_displays() {
+        let left = Display::with(0, BBox::with(0, 0, 1920, 1080));
+        let right = Display::with(1, BBox::with(1920, 0, 1920, 1080));
+        let mut manager = Manager::with(vec![
+            workspace_with_tiles(0, left, &[0, 1]),
+            workspace_with_tiles(1, right, &[2, 3]),
+        ]);
+        manager.focused_workspace_id = Some(0);
+        manager.focused_workspace_mut().unwrap().focus_tile(0);
+
+        assert!(manager.focus_direction(Direction::Right));
+        assert_eq!(Some(0), manager.focused_workspace_id);
+        assert_eq!(
+            Some(1),
+            manager.focused_workspace().unwrap().focused_tile_id
+        );
+
+        assert!(manager.focus_direction(Direction::Right));
+        assert_eq!(Some(1), manager.focused_workspace_id);
+        assert_eq!(
+            Some(2),
+            manager.focused_workspace().unwrap().focused_tile_id
+        );
+
+        assert!(manager.focus_direction(Direction::Right));
+        assert_eq!(
+            Some(3),
+            manager.focused_workspace().unwrap().focused_tile_id
+        );
+        assert!(!manager.focus_direction(Direction::Right));
+        assert!(!manager.focus_direction(Direction::Up));
+    }
+
+    #[test]
+    fn test_focus_history() {
+        let display = Display::with(0, BBox::with(0, 0, 1920, 1080));
+        let mut manager = Manager::with(vec![
+            workspace_with_tiles(0, display.clone(), &[0, 1]),
+            workspace_with_tiles(1, display, &[2, 3]),
+        ]);
+        assert!(manager.focus_tile(0, 0));
+        assert!(manager.focus_tile(1, 2));
+        assert!(manager.focus_tile(1, 3));
+        assert!(!manager.focus_tile(0, 3));
+
+        assert!(manager.focus_last());
+        assert_eq!(Some(1), manager.focused_workspace_id);
+        assert_eq!(&[(1, 2), (1, 3), (0, 0)], manager.focus_history());
+
+        assert!(manager.cycle_focus(true));
+        assert!(manager.cycle_focus(true));
+        assert_eq!(Some(0), manager.focused_workspace_id);
+        assert_eq!(&[(1, 2), (1, 3), (0, 0)], manager.focus_history());
+        manager.finish_focus_cycle();
+        assert_eq!(&[(0, 0), (1, 2), (1, 3)], manager.focus_history());
+
+        manager.workspaces[1].remove_tile_by_id(2).unwrap();
+        assert!(manager.focus_last());
+        assert_eq!(
+            Some(3),
+            manager.focused_workspace().unwrap().focused_tile_id
+        );
+    }
+
+    #[test]
+    fn test_move_tile() {
+        let left = Display::with(0, BBox::with(0, 0, 1920, 1080));
+        let right = Display::with(1, BBox::with(1920, 0, 1920, 1080));
+        let mut manager = Manager::with(vec![
+            workspace_with_tiles(0, left.clone(), &[0, 1]),
+            workspace_with_tiles(1, left, &[]),
+            workspace_with_tiles(2, right, &[2]),
+        ]);
+        manager.focus_tile(0, 1);
+
+        assert!(manager
+            .move_focused(WorkspaceTarget::Display(Direction::Right), false)
+            .unwrap());
+        assert_eq!(Some(0), manager.focused_workspace_id);
+        assert_eq!(
+            Some(0),
+            manager.focused_workspace().unwrap().focused_tile_id
+        );
+        assert_eq!(
+            BBox::with(0, 0, 1920, 1080),
+            manager.workspaces[0].tiles()[0].bbox
+        );
+        assert_eq!(Some(1), manager.workspaces[2].focused_tile_id);
+        assert_eq!(
+            BBox::with(2880, 0, 960, 1080),
+            manager.workspaces[2].tile_by_id(1).unwrap().bbox
+        );
+
+        assert!(manager.move_tile(1, WorkspaceTarget::Next, true).unwrap());
+        assert_eq!(Some(0), manager.focused_workspace_id);
+        assert_eq!(
+            Some(1),
+            manager.focused_workspace().unwrap().focused_tile_id
+        );
+        assert_eq!(&[(0, 1)], manager.focus_history());
+
+        assert!(manager.move_focused(WorkspaceTarget::Id(1), true).unwrap());
+        assert_eq!(Some(1), manager.focused_workspace_id);
+        assert!(!manager.move_focused(WorkspaceTarget::Id(1), true).unwrap());
+        assert!(!manager
+            .move_tile(42, WorkspaceTarget::Previous, false)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_move_tile_rejected_by_target() {
+        let display = Display::with(0, BBox::with(0, 0, 1920, 1080));
+        let mut manager = Manager::with(vec![
+            workspace_with_tiles(0, display.clone(), &[0, 1, 2]),
+            workspace_with_tiles(1, display, &[1]),
+        ]);
+
+        manager.focus_tile(0, 2);
+        let bboxes: Vec<BBox> = manager.workspaces[0].iter().map(|tile| tile.bbox).collect();
+
+        assert_eq!(
+            Err(Error::DuplicateId {
+                kind: IdKind::Tile,
+                id: 1
+            }),
+            manager.move_tile(1, WorkspaceTarget::Id(1), false)
+        );
+        assert!(!manager.workspaces[0].layout.is_dirty());
+        assert_eq!(Some(2), manager.workspaces[0].focused_tile_id);
+        assert_eq!(
+            bboxes,
+            manager.workspaces[0]
+                .iter()
+                .map(|tile| tile.bbox)
+                .collect::<Vec<BBox>>()
+        );
+        let tile_ids: Vec<TileId> = manager.workspaces[0].iter().map(|tile| tile.id).collect();
+        assert_eq!(vec![0, 1, 2], tile_ids);
+        assert_eq!(1, manager.workspaces[1].tiles().len());
+    }
+
+    #[test]
+    fn test_switch_to_workspace() {
+        let left = Display::with(0, BBox::with(0, 0, 1920, 1080));
+        let right = Display::with(1, BBox::with(1920, 0, 1920, 1080));
+        let mut manager = Manager::with(vec![
+            workspace_with_tiles(0, left.clone(), &[0]),
+            workspace_with_tiles(1, left, &[1]),
+            workspace_with_tiles(2, right, &[2]),
+        ]);
+        manager.focused_workspace_id = Some(0);
+        assert_eq!(Some(0), manager.visible_workspace_id(0));
+        assert_eq!(Some(2), manager.visible_workspace_id(1));
+
+        assert!(manager.switch_to_workspace(1));
+        assert_eq!(Some(1), manager.visible_workspace_id(0));
+        assert!(!manager.is_workspace_visible(0));
+        let expected = vec![
+            VisibilityChange::Hide { workspace_id: 0 },
+            VisibilityChange::Show {
+                workspace_id: 1,
+                display_id: 0,
+            },
+        ];
+        assert_eq!(expected, manager.take_visibility_changes());
+
+        // Switching to a visible workspace only moves the focus.
+        assert!(manager.switch_to_workspace(2));
+        assert_eq!(Some(1), manager.focused_display_id());
+        assert!(manager.take_visibility_changes().is_empty());
+
+        assert!(!manager.switch_to_workspace(2));
+        manager.back_and_forth = true;
+        assert!(manager.switch_to_workspace(2));
+        assert_eq!(Some(1), manager.focused_workspace_id);
+        assert!(manager.switch_back_and_forth());
+        assert_eq!(Some(2), manager.focused_workspace_id);
+    }
+
+    #[test]
+    fn test_swap_visible_workspaces() {
+        let left = Display::with(0, BBox::with(0, 0, 1920, 1080));
+        let right = Display::with(1, BBox::with(1920, 0, 1280, 1024));
+        let mut manager = Manager::with(vec![
+            workspace_with_tiles(0, left, &[0]),
+            workspace_with_tiles(1, right, &[1]),
+        ]);
+        manager.focus_tile(0, 0);
+        manager.take_visibility_changes();
+
+        assert!(manager.swap_visible_workspaces(0, 1));
+        assert_eq!(Some(1), manager.visible_workspace_id(0));
+        assert_eq!(Some(0), manager.visible_workspace_id(1));
+        assert_eq!(Some(1), manager.focused_display_id());
+        assert_eq!(
+            BBox::with(1920, 0, 1280, 1024),
+            manager.workspaces[0].tiles()[0].bbox
+        );
+        assert_eq!(2, manager.take_visibility_changes().len());
+    }
+
+    #[test]
+    fn test_display_topology() {
+        let left = Display::with(0, BBox::with(-1920, 0, 1920, 1080));
+        let right = Display::with(1, BBox::with(0, 0, 1920, 1080));
+        let mut manager = Manager::with(vec![
+            workspace_with_tiles(0, left, &[]),
+            workspace_with_tiles(1, right, &[]),
+        ]);
+
+        assert_eq!(1, manager.topology().primary().unwrap().id);
+        assert!(manager.set_primary_display(0));
+        assert!(!manager.set_primary_display(2));
+        assert_eq!(0, manager.topology().primary().unwrap().id);
+
+        manager
+            .add_window(1, 7, BBox::with(-600, 100, 640, 480))
+            .unwrap();
+        let window_id = manager.window_id(7).unwrap();
+        assert_eq!(0, manager.display_of_window(window_id).unwrap().id);
+    }
+
+    #[test]
+    fn test_reserve_area() {
+        let display = Display::with(0, BBox::with(0, 0, 1920, 1080));
+        let mut manager = Manager::with(vec![workspace_with_tiles(0, display, &[0])]);
+
+        let id = manager
+            .reserve_area(0, Reservation::with(Edge::Top, 30))
+            .unwrap();
+        assert_eq!(
+            BBox::with(0, 30, 1920, 1050),
+            manager.workspaces[0].tiles()[0].bbox
+        );
+        assert_eq!(
+            None,
+            manager.reserve_area(1, Reservation::with(Edge::Top, 30))
+        );
+
+        assert_eq!(
+            Some(Reservation::with(Edge::Top, 30)),
+            manager.release_area(id)
+        );
+        assert_eq!(None, manager.release_area(id));
+        assert_eq!(
+            BBox::with(0, 0, 1920, 1080),
+            manager.workspaces[0].tiles()[0].bbox
+        );
+    }
+
+    #[test]
+    fn test_dynamic_workspaces() {
+        let display = Display::with(0, BBox::with(0, 0, 1920, 1080));
+        let mut manager = Manager::new();
+        manager.add_display(display);
+        let mut config = Config::default();
+        config.workspaces.push(WorkspaceConfig {
+            name: "code".into(),
+            label: Some("C".into()),
+            display: None,
+            persistent: true,
+        });
+        manager.apply_config(&config);
+
+        let code = manager.workspace_by_name("code").unwrap().id;
+        assert_eq!(Some("C".to_string()), manager.workspaces[0].label);
+        assert!(manager.switch_to_workspace(code));
+
+        assert!(manager.switch_to_workspace_by_name("web"));
+        let web = manager.focused_workspace_id.unwrap();
+        assert_ne!(code, web);
+        assert!(!manager.rename_workspace(web, "code"));
+        assert!(manager.rename_workspace(web, "mail"));
+
+        // Empty workspaces are destroyed once they are hidden.
+        assert!(manager.switch_to_workspace(code));
+        assert!(manager.workspace_by_name("mail").is_none());
+        assert!(manager.switch_to_workspace_by_name("web"));
+        assert!(manager.switch_to_workspace(code));
+        assert_eq!(1, manager.workspaces.len());
+
+        // Persistent workspaces are kept.
+        assert!(manager.switch_to_workspace_by_name("web"));
+        assert!(manager.workspace_by_name("code").is_some());
+    }
+
+    #[test]
+    fn test_workspaces_not_created_on_demand_are_kept() {
+        let display = Display::with(0, BBox::with(0, 0, 1920, 1080));
+        let mut manager = Manager::with(vec![
+            workspace_with_tiles(0, display.clone(), &[0]),
+            workspace_with_tiles(1, display, &[]),
+        ]);
+        manager.focused_workspace_id = Some(0);
+
+        assert!(manager.switch_to_workspace(1));
+        assert!(manager.switch_to_workspace(0));
+        assert_eq!(2, manager.workspaces.len());
+    }
+
+    #[test]
+    fn test_add_window() {
+        let display = Display::with(0, BBox::with(0, 0, 1920, 1080));
+        let mut manager = Manager::with(vec![workspace_with_tiles(3, display, &[0, 4])]);
+        let bbox = BBox::with(100, 100, 640, 480);
+
+        let tile_id = manager.add_window(3, 42, bbox).unwrap();
+        assert_eq!(5, tile_id);
+        let window_id = manager.window_id(42).unwrap();
+        let (workspace_id, tile) = manager.tile_by_window_id(window_id).unwrap();
+        assert_eq!((3, tile_id), (workspace_id, tile.id));
+
+        assert!(manager.add_window(3, 42, bbox).is_err());
+        assert!(manager.add_window(7, 43, bbox).is_err());
+        let duplicate = Tile::with(4, bbox, Window::with(100, 44, bbox));
+        let expected = Error::DuplicateId {
+            kind: IdKind::Tile,
+            id: 4,
+        };
+        assert_eq!(Err(expected), manager.add_tile(3, duplicate));
+
+        assert_eq!(
+            Some(tile_id),
+            manager.remove_tile(tile_id).map(|tile| tile.id)
+        );
+        assert_eq!(None, manager.window_id(42));
+        assert!(manager.add_window(3, 42, bbox).is_ok());
+    }
 }
@@ -15,6 +15,14 @@ pub struct Tile {
     pub bbox: BBox,
     /// The window this tile displays.
     pub window: Window,
+    /// Whether the layout hides the tile. Backends should not show the windows
+    /// of hidden tiles, e.g. by minimizing them.
+    #[serde(default)]
+    pub hidden: bool,
+    /// Whether the tile is floating. Floating tiles keep their own bounding
+    /// box and are not laid out by the workspace's layout.
+    #[serde(default)]
+    pub floating: bool,
 }
 
 impl Tile {
@@ -37,9 +45,15 @@ impl Tile {
     ///
     /// # Note
     ///
-    /// `Tile`s do not keep track of already assigned IDs. It's up to the
-    /// library user to take care of this.
+    /// `Tile`s do not keep track of already assigned IDs. Use
+    /// `Manager::add_window` to create tiles with unique ids.
     pub fn with(id: TileId, bbox: BBox, window: Window) -> Self {
-        Self { id, bbox, window }
+        Self {
+            id,
+            bbox,
+            window,
+            hidden: false,
+            floating: false,
+        }
     }
 }
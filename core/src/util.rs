@@ -1,5 +1,6 @@
 //! Contains model-independent enums, structs and traits.
 
+use crate::bbox::BBox;
 use serde::{Deserialize, Serialize};
 
 /// A general direction.
@@ -21,3 +22,156 @@ impl Default for Direction {
         Direction::Left
     }
 }
+
+impl Direction {
+    /// Returns the direction that follows this one when rotating clockwise.
+    pub fn clockwise(self) -> Self {
+        match self {
+            Direction::Left => Direction::Up,
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+        }
+    }
+
+    /// Returns the opposite direction.
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+}
+
+/// Returns the candidate that is geometrically nearest to the origin in the
+/// given direction.
+///
+/// Candidates that lie completely in the given direction are preferred. They
+/// are ranked by the distance between their edge and the origin's opposite
+/// edge. Ties are broken by the overlap with the origin on the perpendicular
+/// axis (bigger is better), followed by the distance of their centers on that
+/// axis. Candidates that overlap the origin are only considered if no other
+/// candidate exists and if their center lies in the given direction.
+///
+/// # Arguments
+///
+/// - `origin`: The bounding box to start from.
+/// - `direction`: The direction to look in.
+/// - `candidates`: The candidates, together with their bounding box.
+///
+/// # Returns
+///
+/// `Some(T)` if a candidate lies in the given direction, `None` otherwise. If
+/// multiple candidates are ranked equally, the first one is returned.
+pub fn nearest_in_direction<T, I>(origin: BBox, direction: Direction, candidates: I) -> Option<T>
+where
+    I: IntoIterator<Item = (T, BBox)>,
+{
+    candidates
+        .into_iter()
+        .filter_map(|(candidate, bbox)| {
+            let (edge_distance, center_distance, overlap, offset) = match direction {
+                Direction::Left => (
+                    origin.left() - bbox.right(),
+                    center_x(origin) - center_x(bbox),
+                    overlap(origin.top(), origin.bottom(), bbox.top(), bbox.bottom()),
+                    center_y(origin) - center_y(bbox),
+                ),
+                Direction::Right => (
+                    bbox.left() - origin.right(),
+                    center_x(bbox) - center_x(origin),
+                    overlap(origin.top(), origin.bottom(), bbox.top(), bbox.bottom()),
+                    center_y(origin) - center_y(bbox),
+                ),
+                Direction::Up => (
+                    origin.top() - bbox.bottom(),
+                    center_y(origin) - center_y(bbox),
+                    overlap(origin.left(), origin.right(), bbox.left(), bbox.right()),
+                    center_x(origin) - center_x(bbox),
+                ),
+                Direction::Down => (
+                    bbox.top() - origin.bottom(),
+                    center_y(bbox) - center_y(origin),
+                    overlap(origin.left(), origin.right(), bbox.left(), bbox.right()),
+                    center_x(origin) - center_x(bbox),
+                ),
+            };
+
+            let rank = if edge_distance >= 0 {
+                (0, edge_distance)
+            } else if center_distance > 0 {
+                (1, center_distance)
+            } else {
+                return None;
+            };
+            Some(((rank, -overlap, offset.abs()), candidate))
+        })
+        .min_by_key(|(key, _)| *key)
+        .map(|(_, candidate)| candidate)
+}
+
+// Returns the doubled x-position of the center, avoiding fractions.
+fn center_x(bbox: BBox) -> i64 {
+    bbox.left() + bbox.right()
+}
+
+// Returns the doubled y-position of the center, avoiding fractions.
+fn center_y(bbox: BBox) -> i64 {
+    bbox.top() + bbox.bottom()
+}
+
+// Returns the length of the overlap of two ranges.
+fn overlap(start_a: i64, end_a: i64, start_b: i64, end_b: i64) -> i64 {
+    (end_a.min(end_b) - start_a.max(start_b)).max(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_nearest_in_direction() {
+        let origin = BBox::with(0, 0, 100, 100);
+        let candidates = vec![
+            (1, BBox::with(100, 100, 100, 100)),
+            (2, BBox::with(100, 50, 100, 100)),
+            (3, BBox::with(300, 0, 100, 100)),
+            (4, BBox::with(-100, 0, 100, 100)),
+        ];
+
+        assert_eq!(
+            Some(2),
+            nearest_in_direction(origin, Direction::Right, candidates.clone())
+        );
+        assert_eq!(
+            Some(4),
+            nearest_in_direction(origin, Direction::Left, candidates.clone())
+        );
+        assert_eq!(
+            Some(1),
+            nearest_in_direction(origin, Direction::Down, candidates.clone())
+        );
+        assert_eq!(
+            None,
+            nearest_in_direction(origin, Direction::Up, candidates)
+        );
+    }
+
+    #[test]
+    fn test_nearest_in_direction_overlapping() {
+        let origin = BBox::with(0, 0, 100, 100);
+        let candidates = vec![(1, BBox::with(50, 0, 100, 100)), (2, origin)];
+
+        assert_eq!(
+            Some(1),
+            nearest_in_direction(origin, Direction::Right, candidates.clone())
+        );
+        assert_eq!(
+            None,
+            nearest_in_direction(origin, Direction::Left, candidates)
+        );
+    }
+}
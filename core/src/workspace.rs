@@ -1,9 +1,15 @@
 //!
 
 use crate::{
+    bbox::BBox,
     display::Display,
-    layout::{sided_layout::SidedLayout, Layout, LayoutUpdateInfo},
+    error::{Error, IdKind, Result},
+    layout::{
+        gaps::Gaps, sided_layout::SidedLayout, Layout, LayoutMessage, LayoutMessageInfo,
+        LayoutUpdateInfo, ResizeAmount,
+    },
     tile::{Tile, TileId},
+    util::{nearest_in_direction, Direction},
 };
 use derivative::Derivative;
 use serde::{Deserialize, Serialize};
@@ -20,6 +26,15 @@ pub type WorkspaceId = u32;
 pub struct Workspace {
     /// The unique id.
     pub id: WorkspaceId,
+    /// The unique name.
+    #[serde(default)]
+    pub name: String,
+    /// An optional label, e.g. an icon, that is shown instead of the name.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Whether the workspace is kept when it is empty and loses focus.
+    #[serde(default)]
+    pub persistent: bool,
     /// The display the workspace is in.
     pub display: Display,
     /// The tiles inside this workspace.
@@ -28,6 +43,20 @@ pub struct Workspace {
     pub layout: Box<dyn Layout>,
     /// The id of the focused tile. If `None`, no tile is focused.
     pub focused_tile_id: Option<TileId>,
+    /// The gaps applied by the layout.
+    #[serde(default)]
+    gaps: Gaps,
+    /// The ids of all floating tiles, from the bottom-most to the top-most one.
+    #[serde(default)]
+    floating_order: Vec<TileId>,
+    /// The id of the fullscreen tile and its bounding box before it entered
+    /// fullscreen mode.
+    #[serde(default)]
+    fullscreen: Option<(TileId, BBox)>,
+    /// The ids of all tiles that got focused, from the most recently to the
+    /// least recently focused one.
+    #[serde(default)]
+    focus_history: Vec<TileId>,
 }
 
 impl Default for Workspace {
@@ -38,10 +67,17 @@ impl Default for Workspace {
     fn default() -> Self {
         Self {
             id: 0,
+            name: 0.to_string(),
+            label: None,
+            persistent: false,
             display: Display::default(),
             tiles: Vec::default(),
             layout: Box::new(SidedLayout::new()),
             focused_tile_id: None,
+            gaps: Gaps::default(),
+            floating_order: Vec::default(),
+            fullscreen: None,
+            focus_history: Vec::default(),
         }
     }
 }
@@ -52,7 +88,7 @@ impl Workspace {
         Self::default()
     }
 
-    /// Creates a workspace.
+    /// Creates a workspace that is named after its id.
     ///
     /// # Arguments
     ///
@@ -61,51 +97,575 @@ impl Workspace {
     pub fn with(id: WorkspaceId, display: Display) -> Self {
         Self {
             id,
+            name: id.to_string(),
             display,
             ..Self::default()
         }
     }
 
-    /// Lays out all the tiles inside the workspace.
+    /// Lays out all the tiles inside the workspace. Floating tiles are
+    /// skipped.
+    ///
+    /// Tiles are laid out in the usable area of the display, which excludes
+    /// its reserved areas. The fullscreen tile covers the whole display. It
+    /// keeps its place inside of the layout, so the other tiles are not moved.
+    ///
+    /// # Note
+    ///
+    /// Layouts work in logical units, so gaps and sizes scale with the
+    /// display's scale factor. The resulting bounding boxes of the tiles are
+    /// converted back to physical pixels.
     pub fn layout(&mut self) {
+        let dirty = self.layout.is_dirty();
+        if dirty {
+            for tile in &mut self.tiles {
+                tile.hidden = false;
+            }
+        }
+
         let mut update_info = LayoutUpdateInfo {
-            tiles: &mut self.tiles,
-            workspace_bbox: self.display.bbox,
+            tiles: self
+                .tiles
+                .iter_mut()
+                .filter(|tile| !tile.floating)
+                .collect(),
+            workspace_bbox: self.display.to_logical(self.display.usable_bbox()),
+            gaps: self.gaps,
+            focused_tile_id: self.focused_tile_id,
+            focus_history: &self.focus_history,
         };
         self.layout.layout(&mut update_info);
+
+        // Layouts only touch the tiles if they were dirty.
+        if dirty {
+            let display = &self.display;
+            for tile in self.tiles.iter_mut().filter(|tile| !tile.floating) {
+                tile.bbox = display.to_physical(tile.bbox);
+            }
+        }
+
+        if let Some((tile_id, _)) = self.fullscreen {
+            let display_bbox = self.display.bbox;
+            if let Some(tile) = self.tile_by_id_mut(tile_id) {
+                tile.bbox = display_bbox;
+                tile.hidden = false;
+            }
+        }
     }
 
-    /// Adds a new tile to the workspace and focuses it.
+    /// Sends a layout-specific message to the active layout. The layout
+    /// receives the bounding boxes in logical units.
+    ///
+    /// # Arguments
+    ///
+    /// - `message`: The message to send.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the layout handled the message, `false` otherwise.
+    pub fn send_layout_message(&mut self, message: &LayoutMessage) -> bool {
+        let tiles: Vec<Tile> = self
+            .tiles
+            .iter()
+            .filter(|tile| !tile.floating)
+            .map(|tile| Tile {
+                bbox: self.display.to_logical(tile.bbox),
+                ..*tile
+            })
+            .collect();
+        let info = LayoutMessageInfo {
+            tiles: tiles.iter().collect(),
+            workspace_bbox: self.display.to_logical(self.display.usable_bbox()),
+            gaps: self.gaps,
+            focused_tile_id: self.focused_tile_id,
+        };
+        self.layout.handle_message(message, &info)
+    }
+
+    /// Resizes the focused tile by moving its edge that faces the given
+    /// direction. The new size is kept by the layout across relayouts.
+    ///
+    /// # Arguments
+    ///
+    /// - `direction`: The direction of the edge to move.
+    /// - `amount`: The amount to move the edge by. Positive amounts grow the
+    ///   tile, negative amounts shrink it.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the layout resized the tile, `false` otherwise, e.g. if the
+    /// edge lies on the workspace's boundary.
+    pub fn resize_focused(&mut self, direction: Direction, amount: ResizeAmount) -> bool {
+        self.send_layout_message(&LayoutMessage::Resize(direction, amount))
+    }
+
+    /// Moves the workspace to another display and invalidates the layout.
+    /// Floating tiles keep their position relative to the display.
+    ///
+    /// # Arguments
+    ///
+    /// - `display`: The new display of the workspace.
+    pub fn set_display(&mut self, display: Display) {
+        let dx = display.bbox.x.saturating_sub(self.display.bbox.x);
+        let dy = display.bbox.y.saturating_sub(self.display.bbox.y);
+        for tile in self.tiles.iter_mut().filter(|tile| tile.floating) {
+            tile.bbox = tile.bbox.translate(dx, dy);
+        }
+        self.display = display;
+        self.layout.invalidate();
+    }
+
+    /// Returns the gaps applied by the layout.
+    pub fn gaps(&self) -> Gaps {
+        self.gaps
+    }
+
+    /// Sets the gaps applied by the layout and invalidates it.
+    pub fn set_gaps(&mut self, gaps: Gaps) {
+        self.gaps = gaps;
+        self.layout.invalidate();
+    }
+
+    /// Adds a new tile to the workspace, focuses it and invalidates the layout.
+    ///
+    /// The new tile is focused like any other tile, so a fullscreen tile
+    /// leaves fullscreen mode.
+    ///
+    /// # Returns
+    ///
+    /// An error if a `Tile` with the same id is already inside the workspace.
     ///
     /// # Note
     ///
-    /// This method does __NOT__ check if a `Tile` with the same id is already
-    /// inside the workspace. ID management is up to the caller.
-    pub fn add_tile(&mut self, tile: Tile) {
-        self.focused_tile_id = Some(tile.id);
+    /// Only the tiles of this workspace are checked. Use
+    /// `Manager::add_tile` to check the ids of all workspaces.
+    pub fn add_tile(&mut self, tile: Tile) -> Result<()> {
+        if self.tile_by_id(tile.id).is_some() {
+            return Err(Error::DuplicateId {
+                kind: IdKind::Tile,
+                id: i64::from(tile.id),
+            });
+        }
+
+        let tile_id = tile.id;
+        if tile.floating {
+            self.floating_order.push(tile_id);
+        }
         self.tiles.push(tile);
+        self.layout.invalidate();
+        self.focus_tile(tile_id);
+        Ok(())
     }
 
     /// Removes a tile from the workspace.
     ///
-    /// If the `Tile` was focused, the `focused_tile_id` is set to `None`.
-    pub fn remove_tile(&mut self, tile: &Tile) {
-        self.remove_tile_by_id(tile.id);
+    /// If the `Tile` was focused, the focus falls back to the most recently
+    /// focused tile. If no such tile exists, the `focused_tile_id` is set to
+    /// `None`.
+    ///
+    /// # Returns
+    ///
+    /// An error if the tile is not inside the workspace.
+    pub fn remove_tile(&mut self, tile: &Tile) -> Result<()> {
+        self.remove_tile_by_id(tile.id)
+    }
+
+    /// Removes a tile with a given id from the workspace and invalidates the
+    /// layout.
+    ///
+    /// If the `Tile` was focused, the focus falls back to the most recently
+    /// focused tile. If no such tile exists, the `focused_tile_id` is set to
+    /// `None`.
+    ///
+    /// # Returns
+    ///
+    /// An error if the tile is not inside the workspace.
+    pub fn remove_tile_by_id(&mut self, tile_id: TileId) -> Result<()> {
+        self.take_tile(tile_id)
+            .map(|_| ())
+            .ok_or(Error::UnknownTile(tile_id))
     }
 
-    /// Removes a tile with a given id from the workspace.
+    /// Removes a tile with a given id from the workspace and returns it, e.g.
+    /// to move it to another workspace.
     ///
-    /// If the `Tile` was focused, the `focused_tile_id` is set to `None`.
-    pub fn remove_tile_by_id(&mut self, tile_id: TileId) {
+    /// A fullscreen tile leaves fullscreen mode first. If the `Tile` was
+    /// focused, the focus falls back to the most recently focused tile. The
+    /// layout is invalidated.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Tile)` if the tile exists, `None` otherwise.
+    pub fn take_tile(&mut self, tile_id: TileId) -> Option<Tile> {
+        let index = self.iter().position(|tile| tile.id == tile_id)?;
+        self.set_fullscreen(tile_id, false);
+        self.floating_order.retain(|&id| id != tile_id);
+        self.focus_history.retain(|&id| id != tile_id);
+        let tile = self.tiles.remove(index);
+        self.layout.invalidate();
+
         if self.focused_tile_id == Some(tile_id) {
             self.focused_tile_id = None;
+            if let Some(&previous_tile_id) = self.focus_history.first() {
+                self.focus_tile(previous_tile_id);
+            }
+        }
+        Some(tile)
+    }
+
+    /// Focuses a tile. Floating tiles are raised to the top.
+    ///
+    /// Focusing any other tile than the fullscreen tile leaves fullscreen
+    /// mode. If the focus changes, the layout is invalidated, as layouts can
+    /// place the focused tile differently.
+    ///
+    /// # Arguments
+    ///
+    /// - `tile_id`: The id of the tile to focus.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the tile exists, `false` otherwise.
+    pub fn focus_tile(&mut self, tile_id: TileId) -> bool {
+        if self.tile_by_id(tile_id).is_none() {
+            return false;
+        }
+
+        if let Some((fullscreen_tile_id, _)) = self.fullscreen {
+            if fullscreen_tile_id != tile_id {
+                self.set_fullscreen(fullscreen_tile_id, false);
+            }
+        }
+        if self.focused_tile_id != Some(tile_id) {
+            self.focused_tile_id = Some(tile_id);
+            self.layout.invalidate();
+        }
+        self.record_focus(tile_id);
+        self.raise_tile(tile_id);
+        true
+    }
+
+    /// Focuses the tile that was focused before the focused tile.
+    ///
+    /// # Returns
+    ///
+    /// `true` if such a tile exists, `false` otherwise.
+    pub fn focus_last(&mut self) -> bool {
+        match self.focus_history.get(1) {
+            Some(&tile_id) => self.focus_tile(tile_id),
+            None => false,
         }
-        let (index, _) = self
+    }
+
+    /// Returns the ids of all tiles that got focused, from the most recently
+    /// to the least recently focused one.
+    pub fn focus_history(&self) -> &[TileId] {
+        &self.focus_history
+    }
+
+    // Moves the tile to the front of the focus history.
+    fn record_focus(&mut self, tile_id: TileId) {
+        self.focus_history.retain(|&id| id != tile_id);
+        self.focus_history.insert(0, tile_id);
+    }
+
+    /// Returns the tile that is nearest to the focused tile in the given
+    /// direction. Hidden tiles are ignored.
+    ///
+    /// # Returns
+    ///
+    /// `Some(TileId)` if such a tile exists, `None` otherwise.
+    pub fn tile_in_direction(&self, direction: Direction) -> Option<TileId> {
+        let focused_tile = self.focused_tile()?;
+        self.tile_in_direction_of(focused_tile.bbox, direction)
+    }
+
+    /// Returns the tile that is nearest to the given bounding box in the given
+    /// direction. Hidden tiles are ignored.
+    ///
+    /// The bounding box does not need to be inside of the workspace, e.g. to
+    /// find the tile that is nearest to the edge of a neighbouring display.
+    ///
+    /// # Arguments
+    ///
+    /// - `origin`: The bounding box to start from.
+    /// - `direction`: The direction to look in.
+    ///
+    /// # Returns
+    ///
+    /// `Some(TileId)` if such a tile exists, `None` otherwise.
+    pub fn tile_in_direction_of(&self, origin: BBox, direction: Direction) -> Option<TileId> {
+        let candidates = self
             .iter()
-            .enumerate()
-            .find(|(_index, tile)| tile.id == tile_id)
-            .expect("Failed to find tile with right id");
-        self.tiles.remove(index);
+            .filter(|tile| !tile.hidden)
+            .map(|tile| (tile.id, tile.bbox));
+        nearest_in_direction(origin, direction, candidates)
+    }
+
+    /// Focuses the tile that is nearest to the focused tile in the given
+    /// direction.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the focus changed, `false` if no tile lies in that direction.
+    pub fn focus_direction(&mut self, direction: Direction) -> bool {
+        match self.tile_in_direction(direction) {
+            Some(tile_id) => self.focus_tile(tile_id),
+            None => false,
+        }
+    }
+
+    /// Returns the id of the fullscreen tile.
+    ///
+    /// # Returns
+    ///
+    /// `Some(TileId)` if a tile is in fullscreen mode, `None` otherwise.
+    pub fn fullscreen_tile_id(&self) -> Option<TileId> {
+        self.fullscreen.map(|(tile_id, _)| tile_id)
+    }
+
+    /// Sets whether a tile covers the whole display.
+    ///
+    /// Only one tile can be in fullscreen mode at a time, a previous
+    /// fullscreen tile leaves fullscreen mode. Tiles that leave fullscreen mode
+    /// get their previous bounding box back and the layout is invalidated.
+    ///
+    /// # Arguments
+    ///
+    /// - `tile_id`: The id of the tile.
+    /// - `fullscreen`: Whether the tile should be in fullscreen mode.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the tile exists, `false` otherwise.
+    pub fn set_fullscreen(&mut self, tile_id: TileId, fullscreen: bool) -> bool {
+        let bbox = match self.tile_by_id(tile_id) {
+            Some(tile) => tile.bbox,
+            None => return false,
+        };
+        if (self.fullscreen_tile_id() == Some(tile_id)) == fullscreen {
+            return true;
+        }
+
+        if let Some((previous_tile_id, previous_bbox)) = self.fullscreen.take() {
+            if let Some(tile) = self.tile_by_id_mut(previous_tile_id) {
+                tile.bbox = previous_bbox;
+            }
+            self.layout.invalidate();
+        }
+        if fullscreen {
+            self.fullscreen = Some((tile_id, bbox));
+        }
+        true
+    }
+
+    /// Toggles whether a tile covers the whole display.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the tile exists, `false` otherwise.
+    pub fn toggle_fullscreen(&mut self, tile_id: TileId) -> bool {
+        let fullscreen = self.fullscreen_tile_id() != Some(tile_id);
+        self.set_fullscreen(tile_id, fullscreen)
+    }
+
+    /// Sets whether a tile is floating.
+    ///
+    /// Floating tiles start out with the original bounding box of their window
+    /// and are stacked above all tiled tiles. Both cases invalidate the layout.
+    ///
+    /// # Arguments
+    ///
+    /// - `tile_id`: The id of the tile.
+    /// - `floating`: Whether the tile should float.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the tile exists, `false` otherwise.
+    pub fn set_floating(&mut self, tile_id: TileId, floating: bool) -> bool {
+        let tile = match self.tile_by_id_mut(tile_id) {
+            Some(tile) => tile,
+            None => return false,
+        };
+        if tile.floating == floating {
+            return true;
+        }
+
+        tile.floating = floating;
+        if floating {
+            tile.bbox = tile.window.original_bbox;
+            tile.hidden = false;
+            self.floating_order.push(tile_id);
+        } else {
+            self.floating_order.retain(|&id| id != tile_id);
+        }
+        self.layout.invalidate();
+        true
+    }
+
+    /// Toggles whether a tile is floating.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the tile exists, `false` otherwise.
+    pub fn toggle_floating(&mut self, tile_id: TileId) -> bool {
+        match self.tile_by_id(tile_id) {
+            Some(tile) => {
+                let floating = !tile.floating;
+                self.set_floating(tile_id, floating)
+            }
+            None => false,
+        }
+    }
+
+    /// Raises a floating tile above all other floating tiles. Tiled tiles are
+    /// ignored.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the tile is floating, `false` otherwise.
+    pub fn raise_tile(&mut self, tile_id: TileId) -> bool {
+        match self.floating_order.iter().position(|&id| id == tile_id) {
+            Some(index) => {
+                self.floating_order.remove(index);
+                self.floating_order.push(tile_id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the ids of all tiles in their stacking order, from the
+    /// bottom-most to the top-most one.
+    ///
+    /// Tiled tiles come first, in the order of the workspace. Floating tiles
+    /// are stacked above them. The fullscreen tile is always the top-most one.
+    pub fn stacking_order(&self) -> Vec<TileId> {
+        let fullscreen_tile_id = self.fullscreen_tile_id();
+        self.iter()
+            .filter(|tile| !tile.floating)
+            .map(|tile| tile.id)
+            .chain(self.floating_order.iter().cloned())
+            .filter(|&id| Some(id) != fullscreen_tile_id)
+            .chain(fullscreen_tile_id)
+            .collect()
+    }
+
+    /// Promotes a tile to be the master tile by moving it to the front of the
+    /// workspace's order. The order of the other tiles is kept.
+    ///
+    /// # Arguments
+    ///
+    /// - `tile_id`: The id of the tile to promote.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the tile exists, `false` otherwise.
+    pub fn promote_tile(&mut self, tile_id: TileId) -> bool {
+        match self.iter().position(|tile| tile.id == tile_id) {
+            Some(index) => {
+                let tile = self.tiles.remove(index);
+                self.tiles.insert(0, tile);
+                self.layout.invalidate();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Promotes the focused tile to be the master tile.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a tile is focused, `false` otherwise.
+    pub fn promote_focused(&mut self) -> bool {
+        match self.focused_tile_id {
+            Some(tile_id) => self.promote_tile(tile_id),
+            None => false,
+        }
+    }
+
+    /// Swaps the position of two tiles inside the workspace's order.
+    ///
+    /// # Arguments
+    ///
+    /// - `first`: The id of the first tile.
+    /// - `second`: The id of the second tile.
+    ///
+    /// # Returns
+    ///
+    /// `true` if both tiles exist, `false` otherwise.
+    pub fn swap_tiles(&mut self, first: TileId, second: TileId) -> bool {
+        let first = self.iter().position(|tile| tile.id == first);
+        let second = self.iter().position(|tile| tile.id == second);
+        match (first, second) {
+            (Some(first), Some(second)) => {
+                self.tiles.swap(first, second);
+                self.layout.invalidate();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Swaps the focused tile with the tile that is nearest to it in the given
+    /// direction. The focus stays on the moved tile.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the tiles got swapped, `false` if no tile lies in that
+    /// direction.
+    pub fn swap_direction(&mut self, direction: Direction) -> bool {
+        match (self.focused_tile_id, self.tile_in_direction(direction)) {
+            (Some(focused_tile_id), Some(tile_id)) => self.swap_tiles(focused_tile_id, tile_id),
+            _ => false,
+        }
+    }
+
+    /// Moves a tile to the given index inside the workspace's order. The order
+    /// of the other tiles is kept.
+    ///
+    /// # Arguments
+    ///
+    /// - `tile_id`: The id of the tile to move.
+    /// - `index`: The new index of the tile. Indices past the end move the tile
+    ///   to the back.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the tile exists, `false` otherwise.
+    pub fn move_tile(&mut self, tile_id: TileId, index: usize) -> bool {
+        match self.iter().position(|tile| tile.id == tile_id) {
+            Some(position) => {
+                let tile = self.tiles.remove(position);
+                let index = index.min(self.tiles.len());
+                self.tiles.insert(index, tile);
+                self.layout.invalidate();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Rotates the order of all tiles by one position. The focus stays on the
+    /// same tile.
+    ///
+    /// # Arguments
+    ///
+    /// - `forward`: If `true`, every tile moves one position towards the back
+    ///   and the last tile becomes the first one. If `false`, the first tile
+    ///   becomes the last one.
+    pub fn rotate_tiles(&mut self, forward: bool) {
+        if self.tiles.len() < 2 {
+            return;
+        }
+
+        if forward {
+            self.tiles.rotate_right(1);
+        } else {
+            self.tiles.rotate_left(1);
+        }
+        self.layout.invalidate();
     }
 
     /// Returns a reference of all tiles inside the workspace.
@@ -183,3 +743,182 @@ impl IntoIterator for Workspace {
         self.tiles.into_iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bbox::Margins, layout::grid_layout::GridLayout, window::Window};
+    use pretty_assertions::assert_eq;
+
+    fn workspace_with_tiles(number_of_tiles: usize) -> Workspace {
+        let mut workspace = Workspace::with(0, Display::with(0, BBox::with(0, 0, 1920, 1080)));
+        for i in 0..number_of_tiles {
+            let window = Window::with(i as u32, 0, BBox::with(100, 100, 640, 480));
+            workspace
+                .add_tile(Tile::with(i as TileId, BBox::new(), window))
+                .unwrap();
+        }
+
+        workspace
+    }
+
+    #[test]
+    fn test_floating_tiles_are_not_laid_out() {
+        let mut workspace = workspace_with_tiles(2);
+
+        assert!(workspace.toggle_floating(1));
+        workspace.layout();
+
+        assert_eq!(BBox::with(0, 0, 1920, 1080), workspace.tiles()[0].bbox);
+        assert_eq!(BBox::with(100, 100, 640, 480), workspace.tiles()[1].bbox);
+
+        assert!(workspace.toggle_floating(1));
+        workspace.layout();
+
+        assert_eq!(BBox::with(960, 0, 960, 1080), workspace.tiles()[0].bbox);
+        assert_eq!(BBox::with(0, 0, 960, 1080), workspace.tiles()[1].bbox);
+    }
+
+    #[test]
+    fn test_stacking_order() {
+        let mut workspace = workspace_with_tiles(4);
+        workspace.set_floating(0, true);
+        workspace.set_floating(2, true);
+        assert_eq!(vec![1, 3, 0, 2], workspace.stacking_order());
+
+        workspace.focus_tile(0);
+        assert_eq!(vec![1, 3, 2, 0], workspace.stacking_order());
+
+        workspace.remove_tile_by_id(2).unwrap();
+        assert_eq!(vec![1, 3, 0], workspace.stacking_order());
+    }
+
+    #[test]
+    fn test_layout_in_logical_units() {
+        let mut workspace = workspace_with_tiles(2);
+        workspace.display.bbox = BBox::with(0, 0, 3840, 2160);
+        workspace.display.set_scale_factor(2.0);
+        workspace.set_gaps(Gaps::with(10, Margins::uniform(20)));
+        workspace.layout();
+
+        assert_eq!(BBox::with(1930, 40, 1870, 2080), workspace.tiles()[0].bbox);
+        assert_eq!(BBox::with(40, 40, 1870, 2080), workspace.tiles()[1].bbox);
+
+        // Tiles are not scaled again if the layout is not dirty.
+        workspace.layout();
+        assert_eq!(BBox::with(40, 40, 1870, 2080), workspace.tiles()[1].bbox);
+    }
+
+    #[test]
+    fn test_fullscreen() {
+        let mut workspace = workspace_with_tiles(3);
+        workspace.display.bbox = BBox::with(0, 0, 1920, 1080);
+        workspace.set_gaps(Gaps::with(10, Margins::uniform(20)));
+        workspace.layout();
+        let bboxes: Vec<BBox> = workspace.iter().map(|tile| tile.bbox).collect();
+
+        assert!(workspace.toggle_fullscreen(1));
+        workspace.layout();
+        assert_eq!(BBox::with(0, 0, 1920, 1080), workspace.tiles()[1].bbox);
+        assert_eq!(bboxes[0], workspace.tiles()[0].bbox);
+        assert_eq!(vec![0, 2, 1], workspace.stacking_order());
+
+        // Focusing another tile leaves fullscreen mode.
+        assert!(workspace.focus_tile(2));
+        workspace.layout();
+        assert_eq!(None, workspace.fullscreen_tile_id());
+        assert_eq!(bboxes[1], workspace.tiles()[1].bbox);
+    }
+
+    #[test]
+    fn test_new_tile_leaves_fullscreen() {
+        let mut workspace = workspace_with_tiles(2);
+        assert!(workspace.toggle_fullscreen(0));
+        workspace
+            .add_tile(Tile::with(2, BBox::new(), Window::new()))
+            .unwrap();
+        workspace.layout();
+        assert_eq!(None, workspace.fullscreen_tile_id());
+        assert_eq!(Some(2), workspace.focused_tile_id);
+        assert_ne!(BBox::with(0, 0, 1920, 1080), workspace.tiles()[0].bbox);
+    }
+
+    #[test]
+    fn test_focus_direction() {
+        let mut workspace = workspace_with_tiles(4);
+        workspace.layout = Box::new(GridLayout::new());
+        workspace.layout.invalidate();
+        workspace.layout();
+
+        workspace.focus_tile(0);
+        assert!(workspace.focus_direction(Direction::Right));
+        assert_eq!(Some(1), workspace.focused_tile_id);
+        assert!(workspace.focus_direction(Direction::Down));
+        assert_eq!(Some(3), workspace.focused_tile_id);
+        assert!(!workspace.focus_direction(Direction::Right));
+        assert_eq!(Some(3), workspace.focused_tile_id);
+    }
+
+    #[test]
+    fn test_reorder_tiles() {
+        let mut workspace = workspace_with_tiles(4);
+        let order = |workspace: &Workspace| -> Vec<TileId> {
+            workspace.iter().map(|tile| tile.id).collect()
+        };
+
+        assert!(workspace.swap_tiles(0, 2));
+        assert_eq!(vec![2, 1, 0, 3], order(&workspace));
+        assert!(!workspace.swap_tiles(0, 42));
+
+        assert!(workspace.move_tile(2, 10));
+        assert_eq!(vec![1, 0, 3, 2], order(&workspace));
+
+        workspace.rotate_tiles(true);
+        assert_eq!(vec![2, 1, 0, 3], order(&workspace));
+        workspace.rotate_tiles(false);
+        assert_eq!(vec![1, 0, 3, 2], order(&workspace));
+
+        workspace.focus_tile(3);
+        assert!(workspace.promote_focused());
+        assert_eq!(vec![3, 1, 0, 2], order(&workspace));
+        assert!(workspace.layout.is_dirty());
+    }
+
+    #[test]
+    fn test_swap_direction() {
+        let mut workspace = workspace_with_tiles(2);
+        workspace.layout = Box::new(GridLayout::new());
+        workspace.layout.invalidate();
+        workspace.layout();
+
+        workspace.focus_tile(0);
+        assert!(!workspace.swap_direction(Direction::Left));
+        assert!(workspace.swap_direction(Direction::Right));
+        workspace.layout();
+
+        assert_eq!(Some(0), workspace.focused_tile_id);
+        assert_eq!(0, workspace.tiles()[1].id);
+        assert_eq!(
+            BBox::with(960, 0, 960, 1080),
+            workspace.tile_by_id(0).unwrap().bbox
+        );
+    }
+
+    #[test]
+    fn test_focus_history() {
+        let mut workspace = workspace_with_tiles(4);
+        workspace.focus_tile(1);
+        workspace.focus_tile(3);
+        assert_eq!(&[3, 1, 2, 0], workspace.focus_history());
+
+        assert!(workspace.focus_last());
+        assert_eq!(Some(1), workspace.focused_tile_id);
+
+        workspace.remove_tile_by_id(1).unwrap();
+        assert_eq!(Some(3), workspace.focused_tile_id);
+        workspace.remove_tile_by_id(0).unwrap();
+        assert_eq!(Some(3), workspace.focused_tile_id);
+        assert_eq!(&[3, 2], workspace.focus_history());
+        assert_eq!(Err(Error::UnknownTile(0)), workspace.remove_tile_by_id(0));
+    }
+}